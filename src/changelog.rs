@@ -0,0 +1,619 @@
+use semver::Version;
+
+use crate::release::{Category, Entry, ReleaseNotes};
+
+/// Kinds of change, in the order Keep a Changelog lists them
+const SECTIONS: [&str; 6] = [
+    "Added",
+    "Changed",
+    "Deprecated",
+    "Removed",
+    "Fixed",
+    "Security",
+];
+
+/// Conventional Commits types that don't affect users
+const INTERNAL_TYPES: [&str; 8] = [
+    "build", "chore", "ci", "refactor", "style", "test", "tests", "wip",
+];
+
+/// Start of a new changelog file
+const HEADER: &str = "# Changelog
+
+All notable changes to this project will be documented in this file.
+
+The format is based on [Keep a Changelog](https://keepachangelog.com/en/1.1.0/),
+and this project adheres to [Semantic Versioning](https://semver.org/spec/v2.0.0.html).
+";
+
+/// Release heading the changes are filed under
+pub enum Target {
+    Unreleased,
+    Version { version: String, date: String },
+}
+
+impl Target {
+    /// Version heading for a tag or version, dropping a leading "v"
+    pub fn version(tag: &str, date: &str) -> Self {
+        let version = match tag.strip_prefix('v') {
+            Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => rest,
+            _ => tag,
+        };
+        Target::Version {
+            version: version.to_string(),
+            date: date.to_string(),
+        }
+    }
+
+    fn heading(&self) -> String {
+        match self {
+            Target::Unreleased => "## [Unreleased]".to_string(),
+            Target::Version { version, date } => format!("## [{}] - {}", version, date),
+        }
+    }
+
+    /// Whether a release named in `heading` belongs below this one:
+    /// any version under [Unreleased], older versions under newer ones, and
+    /// everything under a version that isn't semver
+    fn is_above(&self, heading: &str) -> bool {
+        let Target::Version { version, .. } = self else {
+            return true;
+        };
+        let Some(version) = parse_version(version) else {
+            return true;
+        };
+        parse_version(heading_name(heading)).is_some_and(|other| other < version)
+    }
+
+    fn matches(&self, heading: &str) -> bool {
+        let name = heading_name(heading);
+        match self {
+            Target::Unreleased => name.eq_ignore_ascii_case("unreleased"),
+            Target::Version { version, .. } => {
+                name == version || name.strip_prefix('v') == Some(version.as_str())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Target {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Target::Unreleased => write!(f, "[Unreleased]"),
+            Target::Version { version, .. } => write!(f, "[{}]", version),
+        }
+    }
+}
+
+/// Result of merging a range into a changelog
+pub struct Update {
+    pub text: String,
+    /// Entries that were not in the changelog before
+    pub added: usize,
+    /// Entries moved from [Unreleased] to a version heading
+    pub moved: usize,
+}
+
+impl Update {
+    pub fn is_unchanged(&self) -> bool {
+        self.added == 0 && self.moved == 0
+    }
+}
+
+/// A bullet under one of `SECTIONS`
+struct Item {
+    section: &'static str,
+    /// Full hash of the commit
+    hash: String,
+    lines: Vec<String>,
+}
+
+/// A `## ` heading and the lines up to the next one
+struct Release {
+    heading: String,
+    lines: Vec<String>,
+}
+
+/// Lines of a release section split into its `### ` subsections
+struct Body {
+    intro: Vec<String>,
+    subsections: Vec<(String, Vec<String>)>,
+    /// Link reference definitions ending the section
+    links: Vec<String>,
+}
+
+/// Merge the notable commits of `notes` into `existing` (a new changelog
+/// when `None`) under `target`.
+///
+/// Commits are recognized by the hash in their entry's parentheses or
+/// commit link, however abbreviated, so commits already listed are skipped,
+/// and filing a version moves its entries out of [Unreleased]. New version
+/// headings go in semver order. Everything else in the file is kept as is.
+pub fn update(existing: Option<&str>, target: &Target, notes: &ReleaseNotes) -> Update {
+    let original = existing.unwrap_or(HEADER);
+    let (preamble, mut releases) = parse(original);
+    let unreleased = releases
+        .iter()
+        .position(|r| Target::Unreleased.matches(&r.heading));
+
+    let listed_elsewhere = |hash: &str| {
+        releases
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| Some(*i) != unreleased)
+            .any(|(_, r)| r.lines.iter().any(|l| mentions(l, hash)))
+            || preamble.iter().any(|l| mentions(l, hash))
+    };
+    let in_unreleased = |hash: &str| {
+        unreleased.is_some_and(|i| releases[i].lines.iter().any(|l| mentions(l, hash)))
+    };
+
+    let mut new_items = Vec::new();
+    let mut moved = Vec::new();
+    for item in items(notes) {
+        if listed_elsewhere(&item.hash) {
+            continue;
+        }
+        match target {
+            Target::Unreleased if in_unreleased(&item.hash) => continue,
+            Target::Version { .. } if in_unreleased(&item.hash) => moved.push(item.hash.clone()),
+            _ => {}
+        }
+        new_items.push(item);
+    }
+
+    let added = new_items.len() - moved.len();
+    if new_items.is_empty() {
+        return Update {
+            text: existing.unwrap_or_default().to_string(),
+            added: 0,
+            moved: 0,
+        };
+    }
+
+    if !moved.is_empty() {
+        if let Some(i) = unreleased {
+            let mut body = Body::parse(&releases[i].lines);
+            body.remove(&moved);
+            releases[i].lines = body.render();
+        }
+    }
+
+    let index = match releases.iter().position(|r| target.matches(&r.heading)) {
+        Some(index) => index,
+        None => {
+            // [Unreleased] comes first, then versions newest first; a version
+            // goes above the first older one, or last
+            let index = releases
+                .iter()
+                .enumerate()
+                .position(|(i, r)| Some(i) != unreleased && target.is_above(&r.heading))
+                .unwrap_or(releases.len());
+            releases.insert(
+                index,
+                Release {
+                    heading: target.heading(),
+                    lines: Vec::new(),
+                },
+            );
+            index
+        }
+    };
+    let mut body = Body::parse(&releases[index].lines);
+    body.add(new_items);
+    releases[index].lines = body.render();
+
+    Update {
+        text: join(&preamble, &releases),
+        added,
+        moved: moved.len(),
+    }
+}
+
+/// Entries for every commit a user would care about
+fn items(notes: &ReleaseNotes) -> Vec<Item> {
+    let mut items = Vec::new();
+    for (category, entries) in &notes.sections {
+        for entry in entries {
+            let Some(section) = section_for(*category, entry) else {
+                continue;
+            };
+            let breaking = if *category == Category::Breaking {
+                "**Breaking:** "
+            } else {
+                ""
+            };
+            let mut lines = vec![format!("- {}{}", breaking, notes.entry_markdown(entry))];
+            if let Some(ref note) = entry.commit.conventional.breaking_change {
+                lines.push(format!("  {}", note));
+            }
+            items.push(Item {
+                section,
+                hash: entry.commit.hash.clone(),
+                lines,
+            });
+        }
+    }
+    items
+}
+
+/// Keep a Changelog section for an entry, or `None` to leave it out
+fn section_for(category: Category, entry: &Entry) -> Option<&'static str> {
+    let kind = entry.commit.conventional.kind.as_deref();
+    if kind == Some("security") || entry.scope.as_deref() == Some("security") {
+        return Some("Security");
+    }
+
+    let first_word = entry
+        .description
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match first_word.as_str() {
+        "remove" | "removes" | "removed" | "drop" | "drops" | "dropped" | "delete" | "deletes"
+        | "deleted" => return Some("Removed"),
+        "deprecate" | "deprecates" | "deprecated" => return Some("Deprecated"),
+        _ => {}
+    }
+
+    match category {
+        Category::Features => Some("Added"),
+        Category::Fixes => Some("Fixed"),
+        Category::Breaking | Category::Performance => Some("Changed"),
+        Category::Documentation => None,
+        Category::Other if kind.is_some_and(|k| INTERNAL_TYPES.contains(&k)) => None,
+        Category::Other => Some("Changed"),
+    }
+}
+
+/// Version in a heading name such as "1.5.0" or "v1.5.0"
+fn parse_version(name: &str) -> Option<Version> {
+    Version::parse(name.strip_prefix('v').unwrap_or(name)).ok()
+}
+
+/// Whether `line` refers to the commit `hash`, by an abbreviation of it in
+/// parentheses or brackets, as in "(a1b2c3d)" and "[a1b2c3d](...)", or in a
+/// commit link. Hex elsewhere in the text doesn't count.
+fn mentions(line: &str, hash: &str) -> bool {
+    let bytes = line.as_bytes();
+    let mut start = 0;
+    while start < bytes.len() {
+        if !bytes[start].is_ascii_hexdigit() {
+            start += 1;
+            continue;
+        }
+        let end = bytes[start..]
+            .iter()
+            .position(|b| !b.is_ascii_hexdigit())
+            .map_or(bytes.len(), |len| start + len);
+        let reference = &line[start..end];
+        let opened = line[..start].ends_with(['(', '[']) || line[..start].ends_with("/commit/");
+        let closed = line[end..].starts_with([')', ']']);
+        if opened && closed && reference.len() >= 7 && hash.starts_with(reference) {
+            return true;
+        }
+        start = end;
+    }
+    false
+}
+
+/// "1.5.0" from "## [1.5.0] - 2026-10-17", "unreleased" from "## Unreleased"
+fn heading_name(heading: &str) -> &str {
+    let name = heading.trim_start_matches('#').trim();
+    let name = name.strip_prefix('[').unwrap_or(name);
+    let end = name
+        .find(|c: char| c == ']' || c.is_whitespace())
+        .unwrap_or(name.len());
+    &name[..end]
+}
+
+fn parse(text: &str) -> (Vec<String>, Vec<Release>) {
+    let mut preamble = Vec::new();
+    let mut releases: Vec<Release> = Vec::new();
+    for line in text.lines() {
+        if line.starts_with("## ") {
+            releases.push(Release {
+                heading: line.to_string(),
+                lines: Vec::new(),
+            });
+        } else {
+            match releases.last_mut() {
+                Some(release) => release.lines.push(line.to_string()),
+                None => preamble.push(line.to_string()),
+            }
+        }
+    }
+    (preamble, releases)
+}
+
+fn join(preamble: &[String], releases: &[Release]) -> String {
+    let mut lines: Vec<&str> = preamble.iter().map(String::as_str).collect();
+    for release in releases {
+        if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+            lines.push("");
+        }
+        lines.push(&release.heading);
+        lines.extend(release.lines.iter().map(String::as_str));
+    }
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    let mut text = lines.join("\n");
+    text.push('\n');
+    text
+}
+
+impl Body {
+    fn parse(lines: &[String]) -> Self {
+        let mut end = lines.len();
+        while end > 0 && (lines[end - 1].trim().is_empty() || is_link_definition(&lines[end - 1])) {
+            end -= 1;
+        }
+        let links = lines[end..]
+            .iter()
+            .filter(|l| !l.trim().is_empty())
+            .cloned()
+            .collect();
+
+        let mut intro = Vec::new();
+        let mut subsections: Vec<(String, Vec<String>)> = Vec::new();
+        for line in &lines[..end] {
+            if let Some(name) = line.strip_prefix("### ") {
+                subsections.push((name.trim().to_string(), Vec::new()));
+            } else {
+                match subsections.last_mut() {
+                    Some((_, entries)) => entries.push(line.clone()),
+                    None => intro.push(line.clone()),
+                }
+            }
+        }
+
+        Self {
+            intro: trim_blank(intro),
+            subsections: subsections
+                .into_iter()
+                .map(|(name, entries)| (name, trim_blank(entries)))
+                .collect(),
+            links,
+        }
+    }
+
+    /// Drop the bullets (with their continuation lines) for any of the
+    /// commits `hashes`, and subsections left empty
+    fn remove(&mut self, hashes: &[String]) {
+        for (_, entries) in &mut self.subsections {
+            let mut kept = Vec::new();
+            let mut removing = false;
+            for line in entries.drain(..) {
+                if line.starts_with(['-', '*']) {
+                    removing = hashes.iter().any(|h| mentions(&line, h));
+                } else if !line.starts_with([' ', '\t']) || line.trim().is_empty() {
+                    removing = false;
+                }
+                if !removing {
+                    kept.push(line);
+                }
+            }
+            *entries = trim_blank(kept);
+        }
+        self.subsections.retain(|(_, entries)| !entries.is_empty());
+    }
+
+    /// Put `items` at the top of their subsections, creating missing ones
+    /// in Keep a Changelog order
+    fn add(&mut self, items: Vec<Item>) {
+        let order = |name: &str| {
+            SECTIONS
+                .iter()
+                .position(|s| s.eq_ignore_ascii_case(name))
+                .unwrap_or(SECTIONS.len())
+        };
+
+        for section in SECTIONS {
+            let lines: Vec<String> = items
+                .iter()
+                .filter(|item| item.section == section)
+                .flat_map(|item| item.lines.iter().cloned())
+                .collect();
+            if lines.is_empty() {
+                continue;
+            }
+
+            match self
+                .subsections
+                .iter_mut()
+                .find(|(name, _)| name.eq_ignore_ascii_case(section))
+            {
+                Some((_, entries)) => {
+                    entries.splice(0..0, lines);
+                }
+                None => {
+                    let index = self
+                        .subsections
+                        .iter()
+                        .position(|(name, _)| order(name) > order(section))
+                        .unwrap_or(self.subsections.len());
+                    self.subsections.insert(index, (section.to_string(), lines));
+                }
+            }
+        }
+    }
+
+    fn render(&self) -> Vec<String> {
+        let mut lines = vec![String::new()];
+        if !self.intro.is_empty() {
+            lines.extend(self.intro.iter().cloned());
+            lines.push(String::new());
+        }
+        for (name, entries) in &self.subsections {
+            lines.push(format!("### {}", name));
+            lines.push(String::new());
+            lines.extend(entries.iter().cloned());
+            lines.push(String::new());
+        }
+        if !self.links.is_empty() {
+            lines.extend(self.links.iter().cloned());
+            lines.push(String::new());
+        }
+        lines
+    }
+}
+
+/// `[1.0.0]: https://...`
+fn is_link_definition(line: &str) -> bool {
+    line.starts_with('[') && line.contains("]: ")
+}
+
+fn trim_blank(mut lines: Vec<String>) -> Vec<String> {
+    while lines.last().is_some_and(|l| l.trim().is_empty()) {
+        lines.pop();
+    }
+    let start = lines
+        .iter()
+        .position(|l| !l.trim().is_empty())
+        .unwrap_or(lines.len());
+    lines.split_off(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::fake::{commit, FakeBackend};
+    use crate::git::{Commit, GitRepo, SummaryOptions};
+
+    const FEAT: &str = "aaaaaaa1f00d0000000000000000000000000000";
+    const FIX: &str = "bbbbbbb2f00d0000000000000000000000000000";
+    const CHORE: &str = "cafe1230f00d0000000000000000000000000000";
+
+    fn commits() -> Vec<Commit> {
+        vec![
+            commit(FEAT, "feat: add export"),
+            commit(FIX, "fix(cli): handle empty input"),
+            commit(CHORE, "chore: bump deps"),
+        ]
+    }
+
+    fn run(existing: Option<&str>, target: &Target, commits: Vec<Commit>) -> Update {
+        let repo = GitRepo::new(Box::new(FakeBackend::new(commits)));
+        let data = repo
+            .collect_summary(&SummaryOptions {
+                branch: "HEAD".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+        update(existing, target, &ReleaseNotes::new(&data, None, None))
+    }
+
+    fn version(version: &str) -> Target {
+        Target::version(version, "2026-10-18")
+    }
+
+    #[test]
+    fn new_changelog() {
+        let update = run(None, &Target::Unreleased, commits());
+
+        assert_eq!(update.added, 2);
+        assert!(update.text.starts_with(HEADER));
+        assert!(update.text.ends_with(
+            "## [Unreleased]\n\n\
+             ### Added\n\n- Add export (aaaaaaa)\n\n\
+             ### Fixed\n\n- **cli:** Handle empty input (bbbbbbb)\n"
+        ));
+    }
+
+    #[test]
+    fn listed_commits_are_recognized_by_any_abbreviation() {
+        // Listed with a longer abbreviation than the fixtures' seven digits,
+        // and the chore's hash appears in prose, which doesn't count
+        let existing = "# Changelog\n\n\
+                        ## [1.0.0] - 2026-01-01\n\n\
+                        ### Added\n\n- Add export (aaaaaaa1f)\n\n\
+                        ### Fixed\n\n- Handle empty input ([bbbbbbb2](https://example.com/commit/bbbbbbb2f00d))\n";
+        let mut fixtures = commits();
+        fixtures.push(commit(
+            "dddddddd00000000000000000000000000000000",
+            "fix: revert cafe123 and friends",
+        ));
+
+        let update = run(Some(existing), &Target::Unreleased, fixtures);
+
+        assert_eq!(update.added, 1);
+        assert!(update.text.contains(
+            "## [Unreleased]\n\n### Fixed\n\n- Revert cafe123 and friends (ddddddd)\n\n## [1.0.0]"
+        ));
+    }
+
+    #[test]
+    fn filing_a_version_moves_unreleased_entries() {
+        let existing = "# Changelog\n\n\
+                        ## [Unreleased]\n\n\
+                        ### Added\n\n- Add export (aaaaaaa)\n- Add import (eeeeeee)\n\n\
+                        ## [1.0.0] - 2026-01-01\n\n### Added\n\n- First release\n";
+
+        let update = run(Some(existing), &version("v1.1.0"), commits());
+
+        assert_eq!((update.added, update.moved), (1, 1));
+        assert_eq!(
+            update.text,
+            "# Changelog\n\n\
+             ## [Unreleased]\n\n### Added\n\n- Add import (eeeeeee)\n\n\
+             ## [1.1.0] - 2026-10-18\n\n\
+             ### Added\n\n- Add export (aaaaaaa)\n\n\
+             ### Fixed\n\n- **cli:** Handle empty input (bbbbbbb)\n\n\
+             ## [1.0.0] - 2026-01-01\n\n### Added\n\n- First release\n"
+        );
+
+        assert!(run(Some(&update.text), &version("1.1.0"), commits()).is_unchanged());
+    }
+
+    #[test]
+    fn versions_go_in_semver_order() {
+        let existing = "# Changelog\n\n\
+                        ## [Unreleased]\n\n\
+                        ## [1.10.0] - 2026-03-01\n\n- Later\n\n\
+                        ## [1.2.0] - 2026-02-01\n\n- Earlier\n";
+        let headings = |target: &Target| -> Vec<String> {
+            run(Some(existing), target, commits())
+                .text
+                .lines()
+                .filter(|l| l.starts_with("## "))
+                .map(|l| heading_name(l).to_string())
+                .collect()
+        };
+
+        assert_eq!(
+            headings(&version("1.9.0")),
+            ["Unreleased", "1.10.0", "1.9.0", "1.2.0"]
+        );
+        assert_eq!(
+            headings(&version("2.0.0")),
+            ["Unreleased", "2.0.0", "1.10.0", "1.2.0"]
+        );
+        assert_eq!(
+            headings(&version("1.0.0")),
+            ["Unreleased", "1.10.0", "1.2.0", "1.0.0"]
+        );
+    }
+
+    #[test]
+    fn remove_drops_bullets_with_their_continuations() {
+        let lines: Vec<String> = "\n### Added\n\n\
+                                  - Add export (aaaaaaa)\n  Exports everything\n\
+                                  - Add import (eeeeeee)\n\n\
+                                  ### Fixed\n\n- Handle empty input (bbbbbbb)\n\n\
+                                  [1.0.0]: https://example.com/compare/v0.9.0...v1.0.0\n"
+            .lines()
+            .map(str::to_string)
+            .collect();
+        let mut body = Body::parse(&lines);
+
+        body.remove(&[FEAT.to_string(), FIX.to_string()]);
+
+        assert_eq!(
+            body.render().join("\n"),
+            "\n### Added\n\n- Add import (eeeeeee)\n\n\
+             [1.0.0]: https://example.com/compare/v0.9.0...v1.0.0\n"
+        );
+    }
+}
@@ -1,35 +1,159 @@
-use clap::{Parser, ValueEnum};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
-#[derive(Debug, Clone, ValueEnum)]
+use crate::config::Config;
+use crate::date::{self, DateRange, TimeZone};
+
+#[derive(Debug, Clone, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum OutputFormat {
     Pretty,
     Markdown,
     Json,
 }
 
+#[derive(Debug, Clone, ValueEnum)]
+pub enum Backend {
+    /// Shell out to the `git` binary
+    Git,
+    /// Read the repository in-process (requires the `native` feature)
+    Native,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum CoAuthorCredit {
+    /// Credit each co-author with all of the commit's lines
+    Full,
+    /// Divide the commit's lines evenly between author and co-authors
+    Split,
+}
+
+#[derive(Debug, Clone, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Provider {
+    /// Claude via the Anthropic API (ANTHROPIC_API_KEY)
+    Anthropic,
+    /// An OpenAI-compatible chat completions API (OPENAI_API_KEY,
+    /// OPENAI_BASE_URL)
+    #[value(name = "openai")]
+    #[serde(rename = "openai")]
+    OpenAi,
+    /// A local or self-hosted Ollama (OLLAMA_HOST)
+    Ollama,
+}
+
+#[derive(Debug, Clone, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GroupBy {
+    /// Leading directories (see --area-depth)
+    Directory,
+    /// Workspace packages from Cargo.toml, package.json/pnpm or go.work
+    Package,
+}
+
 #[derive(Parser, Debug)]
 #[command(name = "git-summary")]
 #[command(about = "Summarize git commits using LLM", long_about = None)]
 pub struct Args {
-    /// Specific date to summarize (YYYY-MM-DD)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Day or period to summarize: YYYY-MM-DD, today, yesterday, this/last week,
+    /// this/last month, this/last quarter, Q3, 2026-Q3, 2026-W41 or 2026-10
     #[arg(long)]
     pub date: Option<String>,
 
-    /// Start date for range (YYYY-MM-DD or relative like "3 days ago")
+    /// Start of the range: a date or period as for --date, "YYYY-MM-DD HH:MM" or "3 days ago"
     #[arg(long)]
     pub since: Option<String>,
 
-    /// End date for range (YYYY-MM-DD or relative like "yesterday")
+    /// End of the range (inclusive): a date or period as for --date, "YYYY-MM-DD HH:MM" or "3 days ago"
     #[arg(long)]
     pub until: Option<String>,
 
-    /// Branch to summarize
-    #[arg(long, short, default_value = "HEAD")]
-    pub branch: String,
+    /// Sprint to summarize: current, previous or a sprint number (see [sprint] in the config)
+    #[arg(long, conflicts_with_all = ["date", "since", "until"])]
+    pub sprint: Option<String>,
+
+    /// Named range for profiles, e.g. "previous full week"; accepts the same forms as --date
+    #[arg(long)]
+    pub range: Option<String>,
+
+    /// Timezone for day boundaries: an IANA name like Europe/Berlin, UTC, or +02:00 [default: local]
+    #[arg(long)]
+    pub tz: Option<String>,
+
+    /// Apply a named profile from the config (e.g. "weekly")
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Branch or revision range (A..B, A...B) to summarize [default: HEAD]
+    #[arg(long, short)]
+    pub branch: Option<String>,
+
+    /// Leave out commits reachable from this ref (tag, branch or SHA), e.g. --from v1.4.0
+    #[arg(long)]
+    pub from: Option<String>,
+
+    /// End of the range started with --from or --since-last-tag (same as --branch)
+    #[arg(long, conflicts_with = "branch")]
+    pub to: Option<String>,
+
+    /// Start after the most recent tag reachable from the branch
+    #[arg(long, conflicts_with = "from")]
+    pub since_last_tag: bool,
+
+    /// Include the suggested next version in JSON reports (reads the history
+    /// since the latest version tag)
+    #[arg(long)]
+    pub next_version: bool,
+
+    /// Only include commits by this author (name or email substring, or "me"); repeatable
+    #[arg(long)]
+    pub author: Vec<String>,
+
+    /// Extra mailmap file for merging author identities (applied after the repo's .mailmap)
+    #[arg(long)]
+    pub mailmap: Option<PathBuf>,
+
+    /// How to credit lines of commits with Co-authored-by trailers
+    #[arg(long, value_enum, default_value = "full")]
+    pub co_author_credit: CoAuthorCredit,
+
+    /// What to group changes by in "By Area" [default: directory]
+    #[arg(long, value_enum)]
+    pub group_by: Option<GroupBy>,
 
-    /// Output format
-    #[arg(long, short, value_enum, default_value = "pretty")]
-    pub format: OutputFormat,
+    /// Number of leading directories that make up an area in "By Area" [default: 1]
+    #[arg(long)]
+    pub area_depth: Option<usize>,
+
+    /// Add a "By Owner" section grouping changes by team from CODEOWNERS
+    #[arg(long)]
+    pub by_owner: bool,
+
+    /// Leave changes to paths matching this glob out of the stats; repeatable
+    #[arg(long)]
+    pub exclude: Vec<String>,
+
+    /// Always count changes to paths matching this glob, overriding excludes; repeatable
+    #[arg(long)]
+    pub include: Vec<String>,
+
+    /// Count lockfile churn instead of excluding it by default
+    #[arg(long)]
+    pub no_default_excludes: bool,
+
+    /// Project config file to use instead of .git-summary.toml in the repo root
+    #[arg(long)]
+    pub config: Option<PathBuf>,
+
+    /// Output format [default: pretty]
+    #[arg(long, short, value_enum)]
+    pub format: Option<OutputFormat>,
 
     /// Include file lists and detailed stats
     #[arg(long, short)]
@@ -39,30 +163,225 @@ pub struct Args {
     #[arg(long, short)]
     pub quiet: bool,
 
-    /// Path to git repository (defaults to current directory)
-    #[arg(long, default_value = ".")]
-    pub repo: String,
+    /// Path to a git repository; repeat to summarize several together
+    /// (defaults to the config's repo list, then the current directory)
+    #[arg(long)]
+    pub repo: Vec<String>,
+
+    /// Summarize every git repository directly inside this directory
+    #[arg(long, value_name = "DIR")]
+    pub scan: Option<PathBuf>,
 
-    /// Enable LLM summary (requires ANTHROPIC_API_KEY)
+    /// How to read the repository (defaults to native when built with the `native` feature)
+    #[cfg_attr(feature = "native", arg(long, value_enum, default_value = "native"))]
+    #[cfg_attr(not(feature = "native"), arg(long, value_enum, default_value = "git"))]
+    pub backend: Backend,
+
+    /// Enable LLM summary (requires the provider's API key)
     #[arg(long)]
     pub llm: bool,
+
+    /// LLM provider [default: anthropic]
+    #[arg(long, value_enum)]
+    pub provider: Option<Provider>,
+
+    /// LLM model [default: depends on the provider]
+    #[arg(long)]
+    pub model: Option<String>,
+
+    /// Maximum length of the LLM reply in tokens [default: 1024]
+    #[arg(long)]
+    pub max_output_tokens: Option<u32>,
+
+    /// LLM API endpoint, e.g. a gateway or self-hosted server
+    #[arg(long)]
+    pub api_base_url: Option<String>,
+
+    /// LLM request timeout in seconds, 0 for none [default: 120]
+    #[arg(long)]
+    pub timeout: Option<u64>,
+
+    /// HTTP(S) proxy for LLM requests [default: from HTTPS_PROXY/HTTP_PROXY]
+    #[arg(long)]
+    pub proxy: Option<String>,
+
+    /// Retries after LLM rate limiting, overload and server errors [default: 4]
+    #[arg(long)]
+    pub max_retries: Option<u32>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Inspect the configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Write Markdown release notes for a GitHub release
+    ReleaseNotes {
+        /// Tag to describe (from the tag before it) or a range such as
+        /// v1.4.0..v1.5.0 [default: changes since the latest tag]
+        range: Option<String>,
+
+        /// Only categorize commits; skip the LLM introduction
+        #[arg(long)]
+        dry: bool,
+
+        /// Base URL for commit links [default: derived from the origin remote]
+        #[arg(long)]
+        repo_url: Option<String>,
+    },
+    /// Print the next semantic version, based on the commits since the
+    /// latest version tag
+    NextVersion,
+    /// Add the changes in a range to CHANGELOG.md, in Keep a Changelog format
+    Changelog {
+        /// Tag to add (from the tag before it) or a range such as
+        /// v1.4.0..v1.5.0 [default: changes since the latest tag]
+        range: Option<String>,
+
+        /// Version heading to file the changes under, or "next" for the
+        /// suggested next version [default: the tag ending the range, else
+        /// Unreleased]
+        #[arg(long)]
+        release: Option<String>,
+
+        /// Changelog to update [default: CHANGELOG.md in the repository root]
+        #[arg(long)]
+        file: Option<PathBuf>,
+
+        /// Fail instead of writing when the changelog is missing entries
+        #[arg(long)]
+        check: bool,
+
+        /// Base URL for commit links [default: derived from the origin remote]
+        #[arg(long)]
+        repo_url: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print the effective configuration and where each value came from
+    Show,
 }
 
 impl Args {
-    /// Resolve date arguments into a (since, until) pair for git log.
-    /// Returns (since, until, used_default) where used_default is true if
-    /// no date parameters were provided and the 1-day default was applied.
-    pub fn resolve_date_range(&self) -> (Option<String>, Option<String>, bool) {
+    /// Resolve date arguments into the window to summarize. Date flags win
+    /// over the range in `config`, which may come from a profile. The flag
+    /// is true if no range was given anywhere and the 1-day default was
+    /// applied. Relative dates are taken relative to `now`.
+    pub fn resolve_date_range(
+        &self,
+        config: &Config,
+        now: DateTime<Utc>,
+    ) -> Result<(DateRange, bool)> {
+        let tz = match config.tz.as_deref() {
+            Some(tz) => tz.parse()?,
+            None => TimeZone::Local,
+        };
+
         if let Some(ref date) = self.date {
-            // Single date: from start of day to end of day
-            let since = format!("{} 00:00:00", date);
-            let until = format!("{} 23:59:59", date);
-            (Some(since), Some(until), false)
+            Ok((date::day_range(date, tz, now)?, false))
+        } else if let Some(ref sprint) = self.sprint {
+            Ok((config.sprint.sprints()?.range(sprint, tz, now)?, false))
         } else if self.since.is_some() || self.until.is_some() {
-            (self.since.clone(), self.until.clone(), false)
+            let range = date::between(self.since.as_deref(), self.until.as_deref(), tz, now)?;
+            Ok((range, false))
+        } else if let Some(ref range) = config.range {
+            Ok((date::day_range(range, tz, now)?, false))
+        } else if config.since.is_some() || config.until.is_some() {
+            let range = date::between(config.since.as_deref(), config.until.as_deref(), tz, now)?;
+            Ok((range, false))
+        } else if self.from.is_some()
+            || self.since_last_tag
+            || config.branch.as_deref().is_some_and(|b| b.contains(".."))
+        {
+            // A ref range already bounds the commits
+            Ok((date::between(None, None, tz, now)?, false))
         } else {
             // No date parameters provided, default to last 1 day
-            (Some("1 day ago".to_string()), None, true)
+            Ok((date::between(Some("1 day ago"), None, tz, now)?, true))
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Monday 2026-10-12, midday UTC
+    fn monday() -> DateTime<Utc> {
+        "2026-10-12T12:00:00Z".parse().unwrap()
+    }
+
+    fn resolve(flags: &[&str], config: &str) -> (String, bool) {
+        let args =
+            Args::try_parse_from(std::iter::once("git-summary").chain(flags.iter().copied()))
+                .unwrap();
+        let mut config: Config = toml::from_str(config).unwrap();
+        config.tz = Some("UTC".to_string());
+        let (range, used_default) = args.resolve_date_range(&config, monday()).unwrap();
+        (range.label, used_default)
+    }
+
+    fn label(text: &str) -> (String, bool) {
+        (text.to_string(), false)
+    }
+
+    const CONFIG: &str = r#"
+        range = "previous full week"
+        [sprint]
+        start = "2026-01-05"
+        length_days = 14
+    "#;
+
+    #[test]
+    fn named_ranges_are_relative_to_today() {
+        assert_eq!(
+            resolve(&[], r#"range = "previous working day""#),
+            label("previous working day (2026-10-09)")
+        );
+        assert_eq!(
+            resolve(&[], r#"range = "previous full week""#),
+            label("previous full week (2026-10-05 to 2026-10-11)")
+        );
+    }
+
+    #[test]
+    fn date_flags_win_over_the_configured_range() {
+        let all = ["--date", "yesterday", "--since", "2026-10-01"];
+        assert_eq!(resolve(&all, CONFIG), label("yesterday (2026-10-11)"));
+        assert_eq!(
+            resolve(&["--sprint", "previous"], CONFIG),
+            label("Sprint 20 (2026-09-28 to 2026-10-11)")
+        );
+        assert_eq!(
+            resolve(&["--since", "2026-10-01"], CONFIG),
+            label("since 2026-10-01")
+        );
+        assert_eq!(
+            resolve(&[], CONFIG),
+            label("previous full week (2026-10-05 to 2026-10-11)")
+        );
+        assert_eq!(
+            resolve(&[], r#"since = "2026-09-01""#),
+            label("since 2026-09-01")
+        );
+    }
+
+    #[test]
+    fn ref_ranges_need_no_default_window() {
+        assert_eq!(resolve(&["--from", "v1.0.0"], ""), label("all time"));
+        assert_eq!(resolve(&["--since-last-tag"], ""), label("all time"));
+        assert_eq!(
+            resolve(&[], r#"branch = "v1.0.0..main""#),
+            label("all time")
+        );
+        assert_eq!(
+            resolve(&["--from", "v1.0.0"], CONFIG),
+            label("previous full week (2026-10-05 to 2026-10-11)")
+        );
+        assert_eq!(resolve(&[], ""), ("since 1 day ago".to_string(), true));
+    }
+}
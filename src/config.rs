@@ -0,0 +1,793 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::cli::{GroupBy, OutputFormat, Provider};
+use crate::date::Sprints;
+use crate::git::AreaRule;
+use crate::llm;
+use crate::summarizer::PromptStyle;
+
+/// Name of the project configuration file, looked up in the repository root
+pub const CONFIG_FILE_NAME: &str = ".git-summary.toml";
+
+/// One layer of configuration: the user config, `.git-summary.toml`, a
+/// profile, or the command line. Unset values fall through to the layer
+/// below.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    /// Named range such as "previous full week"; wins over `since`/`until`
+    pub range: Option<String>,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Timezone for day boundaries, e.g. "Europe/Berlin"
+    pub tz: Option<String>,
+    pub format: Option<OutputFormat>,
+    pub verbose: Option<bool>,
+    pub quiet: Option<bool>,
+    pub branch: Option<String>,
+    pub group_by: Option<GroupBy>,
+    pub area_depth: Option<usize>,
+    pub by_owner: Option<bool>,
+    /// Glob-to-area rules, checked in order
+    pub areas: Option<Vec<AreaRule>>,
+    pub exclude: Option<Vec<String>>,
+    pub include: Option<Vec<String>>,
+    /// Repositories to summarize when no `--repo` is given, relative to
+    /// the config file
+    pub repos: Option<Vec<PathBuf>>,
+    pub sprint: SprintConfig,
+    pub llm: LlmConfig,
+    pub prompt: PromptConfig,
+    /// Named bundles of settings selected with `--profile`
+    pub profiles: BTreeMap<String, Config>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SprintConfig {
+    /// First day of any sprint (YYYY-MM-DD)
+    pub start: Option<String>,
+    /// Number of the sprint beginning on `start`
+    pub number: Option<i64>,
+    pub length_days: Option<u32>,
+    /// Sprint name, with `{n}` replaced by the sprint number
+    pub name: Option<String>,
+}
+
+impl SprintConfig {
+    pub fn sprints(&self) -> Result<Sprints> {
+        let start = self
+            .start
+            .as_deref()
+            .context("--sprint needs a sprint start date, e.g. `[sprint] start = \"2026-01-05\"` in the config")?;
+        let length_days = self.length_days.unwrap_or(14);
+        if length_days == 0 {
+            anyhow::bail!("Invalid sprint length: length_days must be at least 1");
+        }
+        Ok(Sprints {
+            start: NaiveDate::parse_from_str(start, "%Y-%m-%d").with_context(|| {
+                format!("Invalid sprint start date: {} (expected YYYY-MM-DD)", start)
+            })?,
+            number: self.number.unwrap_or(1),
+            length_days,
+            name: self
+                .name
+                .clone()
+                .unwrap_or_else(|| "Sprint {n}".to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct LlmConfig {
+    /// Summarize with the LLM without passing `--llm`
+    pub enabled: Option<bool>,
+    pub provider: Option<Provider>,
+    pub model: Option<String>,
+    pub max_output_tokens: Option<u32>,
+    /// Endpoint overriding the provider's default and environment variable
+    pub api_base_url: Option<String>,
+    /// Request timeout in seconds; 0 waits indefinitely
+    pub timeout: Option<u64>,
+    /// HTTP(S) proxy URL for LLM requests
+    pub proxy: Option<String>,
+    /// Retries after rate limiting, overload and server errors
+    pub max_retries: Option<u32>,
+}
+
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PromptConfig {
+    pub style: Option<PromptStyle>,
+    /// Extra instructions appended to the built-in prompt
+    pub instructions: Option<String>,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config {}", path.display()))?;
+        let mut config: Self =
+            toml::from_str(&text).with_context(|| format!("Invalid config {}", path.display()))?;
+
+        if let Some(dir) = path.parent() {
+            let profiles = config.profiles.values_mut();
+            for repo in std::iter::once(&mut config.repos)
+                .chain(profiles.map(|p| &mut p.repos))
+                .flatten()
+                .flatten()
+            {
+                *repo = dir.join(&*repo);
+            }
+        }
+        if let Some((name, _)) = config.profiles.iter().find(|(_, p)| !p.profiles.is_empty()) {
+            anyhow::bail!(
+                "Invalid config {}: profile {} cannot define profiles",
+                path.display(),
+                name
+            );
+        }
+        Ok(config)
+    }
+
+    /// Overlay `other` on top of this config; values set in `other` win
+    fn merge(self, other: Config) -> Config {
+        // The date range is one setting: a layer that sets any part of it
+        // replaces the whole range below
+        let (range, since, until) =
+            if other.range.is_some() || other.since.is_some() || other.until.is_some() {
+                (other.range, other.since, other.until)
+            } else {
+                (self.range, self.since, self.until)
+            };
+
+        let mut profiles = self.profiles;
+        profiles.extend(other.profiles);
+
+        Config {
+            range,
+            since,
+            until,
+            tz: other.tz.or(self.tz),
+            format: other.format.or(self.format),
+            verbose: other.verbose.or(self.verbose),
+            quiet: other.quiet.or(self.quiet),
+            branch: other.branch.or(self.branch),
+            group_by: other.group_by.or(self.group_by),
+            area_depth: other.area_depth.or(self.area_depth),
+            by_owner: other.by_owner.or(self.by_owner),
+            areas: other.areas.or(self.areas),
+            exclude: other.exclude.or(self.exclude),
+            include: other.include.or(self.include),
+            repos: other.repos.or(self.repos),
+            sprint: SprintConfig {
+                start: other.sprint.start.or(self.sprint.start),
+                number: other.sprint.number.or(self.sprint.number),
+                length_days: other.sprint.length_days.or(self.sprint.length_days),
+                name: other.sprint.name.or(self.sprint.name),
+            },
+            llm: LlmConfig {
+                enabled: other.llm.enabled.or(self.llm.enabled),
+                provider: other.llm.provider.or(self.llm.provider),
+                model: other.llm.model.or(self.llm.model),
+                max_output_tokens: other.llm.max_output_tokens.or(self.llm.max_output_tokens),
+                api_base_url: other.llm.api_base_url.or(self.llm.api_base_url),
+                timeout: other.llm.timeout.or(self.llm.timeout),
+                proxy: other.llm.proxy.or(self.llm.proxy),
+                max_retries: other.llm.max_retries.or(self.llm.max_retries),
+            },
+            prompt: PromptConfig {
+                style: other.prompt.style.or(self.prompt.style),
+                instructions: other.prompt.instructions.or(self.prompt.instructions),
+            },
+            profiles,
+        }
+    }
+}
+
+/// Where a configuration value came from
+#[derive(Debug, Clone)]
+pub enum Source {
+    Default,
+    User(PathBuf),
+    Project(PathBuf),
+    Profile(String),
+    Cli,
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::User(path) => write!(f, "user config {}", path.display()),
+            Source::Project(path) => write!(f, "project config {}", path.display()),
+            Source::Profile(name) => write!(f, "profile {}", name),
+            Source::Cli => write!(f, "command line"),
+        }
+    }
+}
+
+/// The configuration layers in increasing order of precedence
+pub struct LayeredConfig {
+    layers: Vec<(Source, Config)>,
+}
+
+impl LayeredConfig {
+    /// Load the user config, the project config and the selected profile.
+    /// `explicit` replaces the `.git-summary.toml` lookup in `repo_root`.
+    pub fn load(
+        explicit: Option<&Path>,
+        repo_root: Option<&Path>,
+        profile: Option<&str>,
+    ) -> Result<Self> {
+        Self::load_from(user_config_path(), explicit, repo_root, profile)
+    }
+
+    fn load_from(
+        user: Option<PathBuf>,
+        explicit: Option<&Path>,
+        repo_root: Option<&Path>,
+        profile: Option<&str>,
+    ) -> Result<Self> {
+        let mut layers = Vec::new();
+
+        if let Some(path) = user.filter(|p| p.is_file()) {
+            layers.push((Source::User(path.clone()), Config::load(&path)?));
+        }
+
+        let project = match explicit {
+            Some(path) => Some(path.to_path_buf()),
+            None => repo_root
+                .map(|root| root.join(CONFIG_FILE_NAME))
+                .filter(|p| p.is_file()),
+        };
+        if let Some(path) = project {
+            layers.push((Source::Project(path.clone()), Config::load(&path)?));
+        }
+
+        if let Some(name) = profile {
+            // A project profile replaces a user profile of the same name
+            let found = layers
+                .iter()
+                .rev()
+                .find_map(|(_, config)| config.profiles.get(name).cloned());
+            let Some(config) = found else {
+                let mut available: Vec<&str> = layers
+                    .iter()
+                    .flat_map(|(_, config)| config.profiles.keys().map(String::as_str))
+                    .collect();
+                available.sort();
+                available.dedup();
+                if available.is_empty() {
+                    anyhow::bail!("Unknown profile: {} (no profiles are configured)", name);
+                }
+                anyhow::bail!(
+                    "Unknown profile: {} (available: {})",
+                    name,
+                    available.join(", ")
+                );
+            };
+            layers.push((Source::Profile(name.to_string()), config));
+        }
+
+        Ok(Self { layers })
+    }
+
+    /// Add the values given on the command line as the top layer
+    pub fn with_cli(mut self, cli: Config) -> Self {
+        self.layers.push((Source::Cli, cli));
+        self
+    }
+
+    /// All layers merged into one
+    pub fn merged(&self) -> Config {
+        self.layers
+            .iter()
+            .fold(Config::default(), |merged, (_, layer)| {
+                merged.merge(layer.clone())
+            })
+    }
+
+    /// The topmost layer that sets a value
+    fn source_of(&self, is_set: impl Fn(&Config) -> bool) -> Source {
+        self.layers
+            .iter()
+            .rev()
+            .find(|(_, layer)| is_set(layer))
+            .map(|(source, _)| source.clone())
+            .unwrap_or(Source::Default)
+    }
+
+    /// Render the effective configuration as TOML, annotating each value
+    /// with where it came from
+    pub fn show(&self) -> Result<String> {
+        let config = self.merged();
+        let mut output = String::new();
+
+        let range_source =
+            self.source_of(|c| c.range.is_some() || c.since.is_some() || c.until.is_some());
+        push_entry(
+            &mut output,
+            "range",
+            config.range.map(toml::Value::String),
+            range_source.clone(),
+        );
+        push_entry(
+            &mut output,
+            "since",
+            config.since.map(toml::Value::String),
+            range_source.clone(),
+        );
+        push_entry(
+            &mut output,
+            "until",
+            config.until.map(toml::Value::String),
+            range_source,
+        );
+        push_entry(
+            &mut output,
+            "tz",
+            Some(toml::Value::String(
+                config.tz.unwrap_or_else(|| "local".to_string()),
+            )),
+            self.source_of(|c| c.tz.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "format",
+            Some(toml::Value::try_from(
+                config.format.unwrap_or(OutputFormat::Pretty),
+            )?),
+            self.source_of(|c| c.format.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "verbose",
+            Some(toml::Value::Boolean(config.verbose.unwrap_or(false))),
+            self.source_of(|c| c.verbose.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "quiet",
+            Some(toml::Value::Boolean(config.quiet.unwrap_or(false))),
+            self.source_of(|c| c.quiet.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "branch",
+            Some(toml::Value::String(
+                config.branch.unwrap_or_else(|| "HEAD".to_string()),
+            )),
+            self.source_of(|c| c.branch.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "group_by",
+            Some(toml::Value::try_from(
+                config.group_by.unwrap_or(GroupBy::Directory),
+            )?),
+            self.source_of(|c| c.group_by.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "area_depth",
+            Some(toml::Value::Integer(config.area_depth.unwrap_or(1) as i64)),
+            self.source_of(|c| c.area_depth.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "by_owner",
+            Some(toml::Value::Boolean(config.by_owner.unwrap_or(false))),
+            self.source_of(|c| c.by_owner.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "areas",
+            Some(toml::Value::try_from(config.areas.unwrap_or_default())?),
+            self.source_of(|c| c.areas.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "exclude",
+            Some(toml::Value::try_from(config.exclude.unwrap_or_default())?),
+            self.source_of(|c| c.exclude.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "include",
+            Some(toml::Value::try_from(config.include.unwrap_or_default())?),
+            self.source_of(|c| c.include.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "repos",
+            Some(toml::Value::try_from(config.repos.unwrap_or_default())?),
+            self.source_of(|c| c.repos.is_some()),
+        );
+
+        output.push_str("\n[sprint]\n");
+        push_entry(
+            &mut output,
+            "start",
+            config.sprint.start.map(toml::Value::String),
+            self.source_of(|c| c.sprint.start.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "number",
+            Some(toml::Value::Integer(config.sprint.number.unwrap_or(1))),
+            self.source_of(|c| c.sprint.number.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "length_days",
+            Some(toml::Value::Integer(
+                config.sprint.length_days.unwrap_or(14).into(),
+            )),
+            self.source_of(|c| c.sprint.length_days.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "name",
+            Some(toml::Value::String(
+                config
+                    .sprint
+                    .name
+                    .unwrap_or_else(|| "Sprint {n}".to_string()),
+            )),
+            self.source_of(|c| c.sprint.name.is_some()),
+        );
+
+        output.push_str("\n[llm]\n");
+        push_entry(
+            &mut output,
+            "enabled",
+            Some(toml::Value::Boolean(config.llm.enabled.unwrap_or(false))),
+            self.source_of(|c| c.llm.enabled.is_some()),
+        );
+        let provider = config.llm.provider.unwrap_or(Provider::Anthropic);
+        push_entry(
+            &mut output,
+            "provider",
+            Some(toml::Value::try_from(&provider)?),
+            self.source_of(|c| c.llm.provider.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "model",
+            Some(toml::Value::String(config.llm.model.unwrap_or_else(|| {
+                llm::default_model(&provider).to_string()
+            }))),
+            self.source_of(|c| c.llm.model.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "max_output_tokens",
+            Some(toml::Value::Integer(
+                config
+                    .llm
+                    .max_output_tokens
+                    .unwrap_or(llm::DEFAULT_MAX_OUTPUT_TOKENS)
+                    .into(),
+            )),
+            self.source_of(|c| c.llm.max_output_tokens.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "api_base_url",
+            config.llm.api_base_url.map(toml::Value::String),
+            self.source_of(|c| c.llm.api_base_url.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "timeout",
+            Some(toml::Value::Integer(
+                config.llm.timeout.unwrap_or(llm::DEFAULT_TIMEOUT_SECS) as i64,
+            )),
+            self.source_of(|c| c.llm.timeout.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "proxy",
+            config.llm.proxy.map(toml::Value::String),
+            self.source_of(|c| c.llm.proxy.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "max_retries",
+            Some(toml::Value::Integer(
+                config
+                    .llm
+                    .max_retries
+                    .unwrap_or(llm::DEFAULT_MAX_RETRIES)
+                    .into(),
+            )),
+            self.source_of(|c| c.llm.max_retries.is_some()),
+        );
+
+        output.push_str("\n[prompt]\n");
+        push_entry(
+            &mut output,
+            "style",
+            Some(toml::Value::try_from(
+                config.prompt.style.unwrap_or_default(),
+            )?),
+            self.source_of(|c| c.prompt.style.is_some()),
+        );
+        push_entry(
+            &mut output,
+            "instructions",
+            config.prompt.instructions.map(toml::Value::String),
+            self.source_of(|c| c.prompt.instructions.is_some()),
+        );
+
+        if !config.profiles.is_empty() {
+            let names: Vec<&str> = config.profiles.keys().map(String::as_str).collect();
+            output.push_str(&format!("\n# Profiles: {}\n", names.join(", ")));
+        }
+
+        Ok(output)
+    }
+}
+
+fn push_entry(output: &mut String, key: &str, value: Option<toml::Value>, source: Source) {
+    match value {
+        Some(value) => output.push_str(&format!("{} = {}  # {}\n", key, value, source)),
+        None => output.push_str(&format!("# {} = (not set)\n", key)),
+    }
+}
+
+/// `$XDG_CONFIG_HOME/git-summary/config.toml`, falling back to `~/.config`
+fn user_config_path() -> Option<PathBuf> {
+    let config_home = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_home.join("git-summary").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Files {
+        dir: tempfile::TempDir,
+    }
+
+    impl Files {
+        fn new() -> Self {
+            Self {
+                dir: tempfile::tempdir().unwrap(),
+            }
+        }
+
+        fn user(&self, text: &str) -> &Self {
+            self.write("user/config.toml", text)
+        }
+
+        fn project(&self, text: &str) -> &Self {
+            self.write(&format!("repo/{}", CONFIG_FILE_NAME), text)
+        }
+
+        fn write(&self, name: &str, text: &str) -> &Self {
+            let path = self.dir.path().join(name);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, text).unwrap();
+            self
+        }
+
+        fn path(&self, name: &str) -> PathBuf {
+            self.dir.path().join(name)
+        }
+
+        fn load(&self, profile: Option<&str>) -> Result<LayeredConfig> {
+            LayeredConfig::load_from(
+                Some(self.path("user/config.toml")),
+                None,
+                Some(&self.path("repo")),
+                profile,
+            )
+        }
+    }
+
+    fn cli(text: &str) -> Config {
+        toml::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn merge_prefers_the_upper_layer_field_by_field() {
+        let lower = cli(r#"
+            branch = "main"
+            format = "markdown"
+            [llm]
+            model = "small"
+            timeout = 30
+            [sprint]
+            start = "2026-01-05"
+        "#);
+        let upper = cli(r#"
+            branch = "develop"
+            [llm]
+            model = "large"
+            [sprint]
+            number = 7
+        "#);
+
+        let merged = lower.merge(upper);
+        assert_eq!(merged.branch.as_deref(), Some("develop"));
+        assert!(matches!(merged.format, Some(OutputFormat::Markdown)));
+        assert_eq!(merged.llm.model.as_deref(), Some("large"));
+        assert_eq!(merged.llm.timeout, Some(30));
+        assert_eq!(merged.sprint.start.as_deref(), Some("2026-01-05"));
+        assert_eq!(merged.sprint.number, Some(7));
+    }
+
+    #[test]
+    fn merge_replaces_the_date_range_as_a_whole() {
+        let lower = cli(r#"
+            since = "2026-01-01"
+            until = "2026-01-31"
+        "#);
+
+        let merged = lower.clone().merge(cli(r#"range = "previous full week""#));
+        assert_eq!(merged.range.as_deref(), Some("previous full week"));
+        assert_eq!((merged.since, merged.until), (None, None));
+
+        let merged = cli(r#"range = "previous full week""#).merge(cli(r#"since = "2026-02-01""#));
+        assert_eq!(merged.range, None);
+        assert_eq!(merged.since.as_deref(), Some("2026-02-01"));
+
+        let merged = lower.merge(Config::default());
+        assert_eq!(merged.since.as_deref(), Some("2026-01-01"));
+        assert_eq!(merged.until.as_deref(), Some("2026-01-31"));
+    }
+
+    #[test]
+    fn project_overrides_user_and_cli_overrides_both() {
+        let files = Files::new();
+        files
+            .user(
+                r#"
+                branch = "main"
+                format = "markdown"
+                exclude = ["vendor/**"]
+                "#,
+            )
+            .project(
+                r#"
+                branch = "develop"
+                format = "json"
+                "#,
+            );
+
+        let config = files
+            .load(None)
+            .unwrap()
+            .with_cli(cli(r#"format = "pretty""#))
+            .merged();
+        assert_eq!(config.branch.as_deref(), Some("develop"));
+        assert!(matches!(config.format, Some(OutputFormat::Pretty)));
+        assert_eq!(config.exclude, Some(vec!["vendor/**".to_string()]));
+    }
+
+    #[test]
+    fn repos_are_relative_to_their_config_file() {
+        let files = Files::new();
+        files
+            .project(
+                r#"
+                repos = ["../api", "/abs/web"]
+                [profiles.team]
+                repos = ["sub"]
+                "#,
+            )
+            .user("");
+
+        let config = files.load(None).unwrap().merged();
+        assert_eq!(
+            config.repos.unwrap(),
+            [files.path("repo/../api"), PathBuf::from("/abs/web")]
+        );
+
+        let config = files.load(Some("team")).unwrap().merged();
+        assert_eq!(config.repos.unwrap(), [files.path("repo/sub")]);
+    }
+
+    #[test]
+    fn show_names_the_source_of_each_value() {
+        let files = Files::new();
+        files
+            .user(
+                r#"
+                branch = "main"
+                since = "2026-01-01"
+                "#,
+            )
+            .project(
+                r#"
+                format = "markdown"
+                [profiles.weekly]
+                range = "previous full week"
+                "#,
+            );
+
+        let show = files
+            .load(Some("weekly"))
+            .unwrap()
+            .with_cli(cli("verbose = true"))
+            .show()
+            .unwrap();
+        let user = files.path("user/config.toml");
+        let project = files.path(&format!("repo/{}", CONFIG_FILE_NAME));
+
+        assert!(show.contains(r#"range = "previous full week"  # profile weekly"#));
+        assert!(show.contains("# since = (not set)"));
+        assert!(show.contains(&format!(
+            "branch = \"main\"  # user config {}",
+            user.display()
+        )));
+        assert!(show.contains(&format!(
+            "format = \"markdown\"  # project config {}",
+            project.display()
+        )));
+        assert!(show.contains("verbose = true  # command line"));
+        assert!(show.contains("quiet = false  # default"));
+        assert!(show.contains("# Profiles: weekly"));
+    }
+
+    #[test]
+    fn project_profiles_replace_user_profiles_and_cli_wins() {
+        let files = Files::new();
+        files
+            .user(
+                r#"
+                [profiles.standup]
+                range = "yesterday"
+                quiet = true
+                "#,
+            )
+            .project(
+                r#"
+                branch = "main"
+                [profiles.standup]
+                range = "previous working day"
+                branch = "develop"
+                "#,
+            );
+
+        let layers = files.load(Some("standup")).unwrap();
+        let config = layers.merged();
+        assert_eq!(config.range.as_deref(), Some("previous working day"));
+        assert_eq!(config.quiet, None);
+        assert_eq!(config.branch.as_deref(), Some("develop"));
+
+        let config = layers
+            .with_cli(cli(r#"
+                since = "2026-10-01"
+                branch = "release"
+            "#))
+            .merged();
+        assert_eq!(config.range, None);
+        assert_eq!(config.since.as_deref(), Some("2026-10-01"));
+        assert_eq!(config.branch.as_deref(), Some("release"));
+    }
+
+    #[test]
+    fn unknown_profiles_list_the_available_ones() {
+        let files = Files::new();
+        files
+            .user("[profiles.standup]\nquiet = true")
+            .project("[profiles.weekly]\nby_owner = true");
+
+        let error = files.load(Some("daily")).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            "Unknown profile: daily (available: standup, weekly)"
+        );
+    }
+}
@@ -0,0 +1,523 @@
+use anyhow::{bail, Context, Result};
+use chrono::{
+    DateTime, Datelike, Days, FixedOffset, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta,
+    Utc, Weekday,
+};
+
+/// Timestamp format handed to git; both backends understand it
+const GIT_FORMAT: &str = "%Y-%m-%d %H:%M:%S %z";
+
+const EXPECTED: &str = "expected YYYY-MM-DD, \"YYYY-MM-DD HH:MM\", \"N days ago\", \
+    today, yesterday, this/last week, this/last month, Q3, 2026-Q3 or 2026-W41";
+
+/// The timezone day boundaries are computed in
+#[derive(Debug, Clone, Copy)]
+pub enum TimeZone {
+    Local,
+    Named(chrono_tz::Tz),
+    Fixed(FixedOffset),
+}
+
+impl std::str::FromStr for TimeZone {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(TimeZone::Local);
+        }
+        if s.starts_with(['+', '-']) {
+            let offset =
+                DateTime::parse_from_str(&format!("2000-01-01 00:00 {}", s), "%Y-%m-%d %H:%M %z")
+                    .with_context(|| format!("Invalid UTC offset: {} (expected e.g. +02:00)", s))?;
+            return Ok(TimeZone::Fixed(*offset.offset()));
+        }
+        s.parse::<chrono_tz::Tz>().map(TimeZone::Named).map_err(|_| {
+            anyhow::anyhow!(
+                "Unknown timezone: {} (expected an IANA name like Europe/Berlin, UTC, or an offset like +02:00)",
+                s
+            )
+        })
+    }
+}
+
+impl TimeZone {
+    /// The wall-clock time in this timezone at the instant `now`
+    fn local(&self, now: DateTime<Utc>) -> NaiveDateTime {
+        match self {
+            TimeZone::Local => now.with_timezone(&chrono::Local).naive_local(),
+            TimeZone::Named(tz) => now.with_timezone(tz).naive_local(),
+            TimeZone::Fixed(offset) => now.with_timezone(offset).naive_local(),
+        }
+    }
+
+    /// Pin a wall-clock time to this timezone. Times skipped by a DST
+    /// change move forward to the first valid time.
+    fn resolve(&self, naive: NaiveDateTime) -> DateTime<FixedOffset> {
+        match self {
+            TimeZone::Local => resolve_in(&chrono::Local, naive),
+            TimeZone::Named(tz) => resolve_in(tz, naive),
+            TimeZone::Fixed(offset) => resolve_in(offset, naive),
+        }
+    }
+}
+
+fn resolve_in<Tz: chrono::TimeZone>(tz: &Tz, naive: NaiveDateTime) -> DateTime<FixedOffset> {
+    (0..4)
+        .find_map(|hours| {
+            tz.from_local_datetime(&(naive + TimeDelta::hours(hours)))
+                .earliest()
+        })
+        .map(|dt| dt.fixed_offset())
+        .expect("DST gaps are shorter than four hours")
+}
+
+/// A resolved `--since`/`--until` window
+#[derive(Debug, Clone)]
+pub struct DateRange {
+    pub since: Option<DateTime<FixedOffset>>,
+    pub until: Option<DateTime<FixedOffset>>,
+    /// Human-readable description for report headers
+    pub label: String,
+}
+
+impl DateRange {
+    pub fn git_since(&self) -> Option<String> {
+        self.since.map(|t| t.format(GIT_FORMAT).to_string())
+    }
+
+    pub fn git_until(&self) -> Option<String> {
+        self.until.map(|t| t.format(GIT_FORMAT).to_string())
+    }
+}
+
+/// Fixed-length sprints counted from a known start date
+#[derive(Debug, Clone)]
+pub struct Sprints {
+    pub start: NaiveDate,
+    /// Number of the sprint beginning on `start`
+    pub number: i64,
+    pub length_days: u32,
+    /// Name template; `{n}` is replaced by the sprint number
+    pub name: String,
+}
+
+impl Sprints {
+    /// The days of the sprint picked by `selector`: "current", "previous"
+    /// or a sprint number
+    pub fn range(&self, selector: &str, tz: TimeZone, now: DateTime<Utc>) -> Result<DateRange> {
+        let length = i64::from(self.length_days);
+        let today = tz.local(now).date();
+        let current = self.number + (today - self.start).num_days().div_euclid(length);
+        let number = match selector.trim().to_lowercase().as_str() {
+            "current" => current,
+            "previous" => current - 1,
+            other => other.parse().with_context(|| {
+                format!(
+                    "Invalid sprint: {} (expected current, previous or a sprint number)",
+                    selector
+                )
+            })?,
+        };
+
+        let first = self
+            .start
+            .checked_add_signed(TimeDelta::days((number - self.number) * length))
+            .context("Sprint is out of range")?;
+        let last = first + Days::new(u64::from(self.length_days) - 1);
+        let name = self.name.replace("{n}", &number.to_string());
+        Ok(days(&name, first, last, tz))
+    }
+}
+
+/// Whole days named by `spec`, such as "2026-10-05", "last week" or "Q3"
+pub fn day_range(spec: &str, tz: TimeZone, now: DateTime<Utc>) -> Result<DateRange> {
+    let Some((first, last)) = parse_days(spec, tz.local(now).date())? else {
+        bail!("Invalid date range: {} ({})", spec, EXPECTED);
+    };
+    Ok(days(spec, first, last, tz))
+}
+
+/// The window between two points in time, either of which may be open
+pub fn between(
+    since: Option<&str>,
+    until: Option<&str>,
+    tz: TimeZone,
+    now: DateTime<Utc>,
+) -> Result<DateRange> {
+    let start = since
+        .map(|s| parse_point(s, tz, now, false).with_context(|| format!("Invalid --since {:?}", s)))
+        .transpose()?;
+    let end = until
+        .map(|u| parse_point(u, tz, now, true).with_context(|| format!("Invalid --until {:?}", u)))
+        .transpose()?;
+
+    if let (Some(start), Some(end)) = (start, end) {
+        if start > end {
+            bail!(
+                "The range starts after it ends: {} is later than {}",
+                since.unwrap_or_default(),
+                until.unwrap_or_default()
+            );
+        }
+    }
+
+    let label = match (since, until) {
+        (Some(s), Some(u)) => format!("{} to {}", s, u),
+        (Some(s), None) => format!("since {}", s),
+        (None, Some(u)) => format!("until {}", u),
+        (None, None) => "all time".to_string(),
+    };
+    Ok(DateRange {
+        since: start,
+        until: end,
+        label,
+    })
+}
+
+/// The range covering `first` through `last`, labelled with `spec` when it
+/// is a name rather than a plain date
+pub fn days(spec: &str, first: NaiveDate, last: NaiveDate, tz: TimeZone) -> DateRange {
+    let dates = if first == last {
+        first.to_string()
+    } else {
+        format!("{} to {}", first, last)
+    };
+    let label = if spec.trim() == dates {
+        dates
+    } else {
+        format!("{} ({})", spec.trim(), dates)
+    };
+    DateRange {
+        since: Some(tz.resolve(first.and_time(NaiveTime::MIN))),
+        until: Some(tz.resolve(end_of_day(last))),
+        label,
+    }
+}
+
+fn end_of_day(date: NaiveDate) -> NaiveDateTime {
+    date.and_hms_opt(23, 59, 59)
+        .expect("23:59:59 is a valid time")
+}
+
+/// A single point in time: the start (or end, for `--until`) of a day or
+/// named range, an explicit time, or "N units ago"
+fn parse_point(
+    spec: &str,
+    tz: TimeZone,
+    now: DateTime<Utc>,
+    end: bool,
+) -> Result<DateTime<FixedOffset>> {
+    let trimmed = spec.trim();
+    let now = tz.local(now);
+
+    if let Some((first, last)) = parse_days(trimmed, now.date())? {
+        return Ok(if end {
+            tz.resolve(end_of_day(last))
+        } else {
+            tz.resolve(first.and_time(NaiveTime::MIN))
+        });
+    }
+
+    if trimmed.eq_ignore_ascii_case("now") {
+        return Ok(tz.resolve(now));
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc3339(trimmed) {
+        return Ok(time);
+    }
+    for format in [
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+    ] {
+        if let Ok(naive) = NaiveDateTime::parse_from_str(trimmed, format) {
+            return Ok(tz.resolve(naive));
+        }
+    }
+
+    if let Some(time) = parse_ago(trimmed, now)? {
+        return Ok(tz.resolve(time));
+    }
+
+    bail!("{}", EXPECTED)
+}
+
+/// "3 days ago", "1 week ago", ... relative to `now`
+fn parse_ago(spec: &str, now: NaiveDateTime) -> Result<Option<NaiveDateTime>> {
+    let words: Vec<&str> = spec.split_whitespace().collect();
+    let [count, unit, "ago"] = words.as_slice() else {
+        return Ok(None);
+    };
+    let Ok(count) = count.parse::<u32>() else {
+        return Ok(None);
+    };
+
+    let time = match unit.trim_end_matches('s').to_lowercase().as_str() {
+        "second" => now.checked_sub_signed(TimeDelta::seconds(count.into())),
+        "minute" => now.checked_sub_signed(TimeDelta::minutes(count.into())),
+        "hour" => now.checked_sub_signed(TimeDelta::hours(count.into())),
+        "day" => now.checked_sub_days(Days::new(count.into())),
+        "week" => now.checked_sub_days(Days::new(u64::from(count) * 7)),
+        "month" => now.checked_sub_months(Months::new(count)),
+        "year" => count
+            .checked_mul(12)
+            .and_then(|months| now.checked_sub_months(Months::new(months))),
+        _ => bail!("Unknown time unit: {}", unit),
+    };
+    time.map(Some)
+        .with_context(|| format!("{} is too far in the past", spec))
+}
+
+/// First and last day of a day or named range relative to `today`, or
+/// `None` if `spec` is not one
+pub fn parse_days(spec: &str, today: NaiveDate) -> Result<Option<(NaiveDate, NaiveDate)>> {
+    let spec = spec.trim();
+    let monday = today - Days::new(today.weekday().num_days_from_monday().into());
+    let month_start = first_of_month(today.year(), today.month())?;
+    let quarter = (today.month0() / 3) as i32;
+
+    let range = match spec.to_lowercase().as_str() {
+        "today" => (today, today),
+        "yesterday" => {
+            let day = today - Days::new(1);
+            (day, day)
+        }
+        "previous working day" => {
+            // Friday when run on a Monday or over the weekend
+            let back = match today.weekday() {
+                Weekday::Mon => 3,
+                Weekday::Sun => 2,
+                _ => 1,
+            };
+            let day = today - Days::new(back);
+            (day, day)
+        }
+        "this week" => (monday, today),
+        "last week" | "previous full week" => (monday - Days::new(7), monday - Days::new(1)),
+        "this month" => (month_start, today),
+        "last month" | "previous full month" => {
+            let last = month_start - Days::new(1);
+            (first_of_month(last.year(), last.month())?, last)
+        }
+        "this quarter" => (quarter_range(today.year(), quarter)?.0, today),
+        "last quarter" => {
+            let (year, quarter) = if quarter == 0 {
+                (today.year() - 1, 3)
+            } else {
+                (today.year(), quarter - 1)
+            };
+            quarter_range(year, quarter)?
+        }
+        "this year" => (first_of_month(today.year(), 1)?, today),
+        "last year" => (
+            first_of_month(today.year() - 1, 1)?,
+            first_of_month(today.year(), 1)? - Days::new(1),
+        ),
+        _ => return parse_period(spec, today),
+    };
+    Ok(Some(range))
+}
+
+/// Calendar periods: `Q3`, `2026-Q3`, `2026-W41`, `2026-10` and `2026-10-05`
+fn parse_period(spec: &str, today: NaiveDate) -> Result<Option<(NaiveDate, NaiveDate)>> {
+    let lower = spec.to_lowercase();
+    let (year, rest) = match lower.split_once('-') {
+        Some((year, rest)) if year.len() == 4 && year.bytes().all(|b| b.is_ascii_digit()) => {
+            (year.parse::<i32>()?, rest)
+        }
+        _ => (today.year(), lower.as_str()),
+    };
+    let explicit_year = rest != lower;
+
+    let quarter = rest
+        .strip_prefix('q')
+        .filter(|q| !q.is_empty() && q.bytes().all(|b| b.is_ascii_digit()));
+    if let Some(q) = quarter {
+        let quarter: u32 = q
+            .parse()
+            .ok()
+            .filter(|q| (1..=4).contains(q))
+            .with_context(|| format!("Invalid quarter: {} (expected Q1 to Q4)", spec))?;
+        return quarter_range(year, quarter as i32 - 1).map(Some);
+    }
+
+    if !explicit_year {
+        return Ok(None);
+    }
+
+    if let Some(w) = rest.strip_prefix('w') {
+        let week: u32 = w
+            .parse()
+            .with_context(|| format!("Invalid ISO week: {}", spec))?;
+        let first = NaiveDate::from_isoywd_opt(year, week, Weekday::Mon).with_context(|| {
+            format!("Invalid ISO week: {} ({} has no week {})", spec, year, week)
+        })?;
+        return Ok(Some((first, first + Days::new(6))));
+    }
+
+    if !rest.bytes().all(|b| b.is_ascii_digit() || b == b'-') {
+        return Ok(None);
+    }
+    match rest.split_once('-') {
+        None => {
+            let month: u32 = rest.parse().context("Invalid month")?;
+            if !(1..=12).contains(&month) {
+                bail!("Invalid month: {}", spec);
+            }
+            let first = first_of_month(year, month)?;
+            let last = first
+                .checked_add_months(Months::new(1))
+                .context("Date out of range")?
+                - Days::new(1);
+            Ok(Some((first, last)))
+        }
+        Some(_) => {
+            let date = NaiveDate::parse_from_str(spec, "%Y-%m-%d")
+                .with_context(|| format!("Invalid date: {}", spec))?;
+            Ok(Some((date, date)))
+        }
+    }
+}
+
+fn first_of_month(year: i32, month: u32) -> Result<NaiveDate> {
+    NaiveDate::from_ymd_opt(year, month, 1).context("Date out of range")
+}
+
+/// First and last day of a zero-based quarter
+fn quarter_range(year: i32, quarter: i32) -> Result<(NaiveDate, NaiveDate)> {
+    let first = first_of_month(year, quarter as u32 * 3 + 1)?;
+    let last = first
+        .checked_add_months(Months::new(3))
+        .context("Date out of range")?
+        - Days::new(1);
+    Ok((first, last))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        s.parse().unwrap()
+    }
+
+    /// First and last day of `spec` on Wednesday 2026-10-14
+    fn span(spec: &str) -> (String, String) {
+        let (first, last) = parse_days(spec, date("2026-10-14")).unwrap().unwrap();
+        (first.to_string(), last.to_string())
+    }
+
+    fn pair(first: &str, last: &str) -> (String, String) {
+        (first.to_string(), last.to_string())
+    }
+
+    #[test]
+    fn named_ranges() {
+        assert_eq!(span("today"), pair("2026-10-14", "2026-10-14"));
+        assert_eq!(span("Yesterday"), pair("2026-10-13", "2026-10-13"));
+        assert_eq!(span("this week"), pair("2026-10-12", "2026-10-14"));
+        assert_eq!(span("last week"), pair("2026-10-05", "2026-10-11"));
+        assert_eq!(span("this month"), pair("2026-10-01", "2026-10-14"));
+        assert_eq!(span("last month"), pair("2026-09-01", "2026-09-30"));
+        assert_eq!(span("this quarter"), pair("2026-10-01", "2026-10-14"));
+        assert_eq!(span("last quarter"), pair("2026-07-01", "2026-09-30"));
+        assert_eq!(span("last year"), pair("2025-01-01", "2025-12-31"));
+    }
+
+    #[test]
+    fn previous_working_day_skips_the_weekend() {
+        let day = |today: &str| {
+            parse_days("previous working day", date(today))
+                .unwrap()
+                .unwrap()
+                .0
+        };
+        assert_eq!(day("2026-10-12"), date("2026-10-09")); // Monday
+        assert_eq!(day("2026-10-11"), date("2026-10-09")); // Sunday
+        assert_eq!(day("2026-10-14"), date("2026-10-13"));
+    }
+
+    #[test]
+    fn calendar_periods() {
+        assert_eq!(span("Q1"), pair("2026-01-01", "2026-03-31"));
+        assert_eq!(span("2025-q4"), pair("2025-10-01", "2025-12-31"));
+        assert_eq!(span("2026-W01"), pair("2025-12-29", "2026-01-04"));
+        assert_eq!(span("2026-W41"), pair("2026-10-05", "2026-10-11"));
+        assert_eq!(span("2024-02"), pair("2024-02-01", "2024-02-29"));
+        assert_eq!(span("2026-10-05"), pair("2026-10-05", "2026-10-05"));
+    }
+
+    #[test]
+    fn invalid_periods() {
+        let today = date("2026-10-14");
+        for spec in ["Q5", "2026-W54", "2026-13", "2026-02-30", "2026-Wx"] {
+            assert!(parse_days(spec, today).is_err(), "{}", spec);
+        }
+        for spec in ["3 days ago", "main", "W41", "2026"] {
+            assert!(parse_days(spec, today).unwrap().is_none(), "{}", spec);
+        }
+    }
+
+    #[test]
+    fn ago() {
+        let now = date("2026-03-31").and_hms_opt(12, 0, 0).unwrap();
+        let ago = |spec: &str| parse_ago(spec, now).unwrap().unwrap().to_string();
+        assert_eq!(ago("90 minutes ago"), "2026-03-31 10:30:00");
+        assert_eq!(ago("1 day ago"), "2026-03-30 12:00:00");
+        assert_eq!(ago("2 weeks ago"), "2026-03-17 12:00:00");
+        assert_eq!(ago("1 month ago"), "2026-02-28 12:00:00");
+        assert!(parse_ago("3 fortnights ago", now).is_err());
+        assert!(parse_ago("yesterday", now).unwrap().is_none());
+    }
+
+    #[test]
+    fn days_span_whole_days_in_the_timezone() {
+        let tz: TimeZone = "Europe/Berlin".parse().unwrap();
+        // Summer time ends during this week
+        let range = days("last week", date("2026-10-19"), date("2026-10-25"), tz);
+        assert_eq!(range.git_since().unwrap(), "2026-10-19 00:00:00 +0200");
+        assert_eq!(range.git_until().unwrap(), "2026-10-25 23:59:59 +0100");
+        assert_eq!(range.label, "last week (2026-10-19 to 2026-10-25)");
+
+        let range = days("2026-10-05", date("2026-10-05"), date("2026-10-05"), tz);
+        assert_eq!(range.label, "2026-10-05");
+    }
+
+    #[test]
+    fn timezones() {
+        let tz: TimeZone = "+05:30".parse().unwrap();
+        let range = between(Some("2026-10-05 09:00"), Some("2026-10-06"), tz, Utc::now()).unwrap();
+        assert_eq!(range.git_since().unwrap(), "2026-10-05 09:00:00 +0530");
+        assert_eq!(range.git_until().unwrap(), "2026-10-06 23:59:59 +0530");
+        assert_eq!(range.label, "2026-10-05 09:00 to 2026-10-06");
+
+        assert!("Mars/Olympus".parse::<TimeZone>().is_err());
+        assert!("+25:00".parse::<TimeZone>().is_err());
+        assert!(between(Some("2026-10-06"), Some("2026-10-05"), tz, Utc::now()).is_err());
+    }
+
+    #[test]
+    fn sprints_by_number() {
+        let sprints = Sprints {
+            start: date("2026-01-05"),
+            number: 10,
+            length_days: 14,
+            name: "Sprint {n}".to_string(),
+        };
+        let tz: TimeZone = "UTC".parse().unwrap();
+
+        let range = sprints.range("12", tz, Utc::now()).unwrap();
+        assert_eq!(range.label, "Sprint 12 (2026-02-02 to 2026-02-15)");
+        let range = sprints.range("9", tz, Utc::now()).unwrap();
+        assert_eq!(range.label, "Sprint 9 (2025-12-22 to 2026-01-04)");
+        assert!(sprints.range("next", tz, Utc::now()).is_err());
+
+        let now = "2026-02-02T08:00:00Z".parse().unwrap();
+        let range = sprints.range("current", tz, now).unwrap();
+        assert_eq!(range.label, "Sprint 12 (2026-02-02 to 2026-02-15)");
+        let range = sprints.range("previous", tz, now).unwrap();
+        assert_eq!(range.label, "Sprint 11 (2026-01-19 to 2026-02-01)");
+    }
+}
@@ -15,6 +15,33 @@ struct JsonOutput<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     area_stats: Option<&'a [crate::git::AreaStats]>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    owner_stats: Option<&'a [crate::git::OwnerStats]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    type_stats: Option<&'a [crate::git::TypeStats]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    author_stats: Option<&'a [crate::git::AuthorStats]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    excluded_stats: Option<&'a [crate::git::ExcludedStats]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_version: Option<&'a crate::git::NextVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    commits: Option<&'a [crate::git::Commit]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    repositories: Option<Vec<JsonRepository<'a>>>,
+}
+
+#[derive(Serialize)]
+struct JsonRepository<'a> {
+    name: &'a str,
+    branch: &'a str,
+    total_commits: usize,
+    total_additions: i32,
+    total_deletions: i32,
+    area_stats: &'a [crate::git::AreaStats],
+    author_stats: &'a [crate::git::AuthorStats],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    next_version: Option<&'a crate::git::NextVersion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     commits: Option<&'a [crate::git::Commit]>,
 }
 
@@ -37,10 +64,35 @@ impl Formatter for JsonFormatter {
                 total_deletions: data.total_deletions,
                 summary,
                 area_stats: Some(&data.area_stats),
-                commits: if verbose {
-                    Some(&data.commits)
+                owner_stats: if data.owner_stats.is_empty() {
+                    None
                 } else {
+                    Some(&data.owner_stats)
+                },
+                type_stats: Some(&data.type_stats),
+                author_stats: Some(&data.author_stats),
+                excluded_stats: Some(&data.excluded_stats),
+                next_version: data.next_version.as_ref(),
+                commits: if verbose { Some(&data.commits) } else { None },
+                repositories: if data.repos.is_empty() {
                     None
+                } else {
+                    Some(
+                        data.repos
+                            .iter()
+                            .map(|repo| JsonRepository {
+                                name: &repo.repo,
+                                branch: &repo.branch,
+                                total_commits: repo.commits.len(),
+                                total_additions: repo.total_additions,
+                                total_deletions: repo.total_deletions,
+                                area_stats: &repo.area_stats,
+                                author_stats: &repo.author_stats,
+                                next_version: repo.next_version.as_ref(),
+                                commits: if verbose { Some(&repo.commits) } else { None },
+                            })
+                            .collect(),
+                    )
                 },
             };
             serde_json::to_string_pretty(&output).unwrap_or_default()
@@ -1,5 +1,5 @@
 use super::Formatter;
-use crate::git::GitSummaryData;
+use crate::git::{Commit, GitSummaryData};
 
 pub struct MarkdownFormatter;
 
@@ -45,6 +45,24 @@ impl Formatter for MarkdownFormatter {
         output.push_str(summary);
         output.push_str("\n\n");
 
+        // By Repository section
+        if !data.repos.is_empty() {
+            output.push_str("## By Repository\n\n");
+            output.push_str("| Repository | Branch | Commits | Lines |\n");
+            output.push_str("|------------|--------|---------|-------|\n");
+            for repo in &data.repos {
+                output.push_str(&format!(
+                    "| {} | {} | {} | +{}/-{} |\n",
+                    repo.repo,
+                    repo.branch,
+                    repo.commits.len(),
+                    repo.total_additions,
+                    repo.total_deletions
+                ));
+            }
+            output.push('\n');
+        }
+
         // By Area section
         if !data.area_stats.is_empty() {
             output.push_str("## By Area\n\n");
@@ -59,26 +77,102 @@ impl Formatter for MarkdownFormatter {
             output.push('\n');
         }
 
-        // Commits section
-        output.push_str("## Commits\n\n");
-        for commit in &data.commits {
-            let date = format_timestamp(&commit.timestamp);
-            if verbose {
+        // By Type section (only when Conventional Commits are in use)
+        if data.commits.iter().any(|c| c.conventional.kind.is_some()) {
+            output.push_str("## By Type\n\n");
+            output.push_str("| Type | Commits | Breaking | Lines |\n");
+            output.push_str("|------|---------|----------|-------|\n");
+            for kind in &data.type_stats {
+                output.push_str(&format!(
+                    "| {} | {} | {} | +{}/-{} |\n",
+                    kind.kind,
+                    kind.commit_count,
+                    kind.breaking_count,
+                    kind.additions,
+                    kind.deletions
+                ));
+            }
+            output.push('\n');
+        }
+
+        // By Owner section
+        if !data.owner_stats.is_empty() {
+            output.push_str("## By Owner\n\n");
+            output.push_str("| Owner | Commits | Lines |\n");
+            output.push_str("|-------|---------|-------|\n");
+            for owner in &data.owner_stats {
                 output.push_str(&format!(
-                    "- `{}` `{}` {}\n",
-                    date, commit.short_hash, commit.message
+                    "| {} | {} | +{}/-{} |\n",
+                    owner.owner, owner.commit_count, owner.additions, owner.deletions
                 ));
-                for file in &commit.files_changed {
-                    output.push_str(&format!(
-                        "  - `{}` (+{}/-{})\n",
-                        file.path, file.additions, file.deletions
-                    ));
-                }
-            } else {
-                output.push_str(&format!("- `{}` {}\n", date, commit.message));
+            }
+            output.push('\n');
+        }
+
+        // Contributors section
+        if !data.author_stats.is_empty() {
+            output.push_str("## Contributors\n\n");
+            output.push_str("| Contributor | Commits | Co-authored | Lines |\n");
+            output.push_str("|-------------|---------|-------------|-------|\n");
+            for author in &data.author_stats {
+                output.push_str(&format!(
+                    "| {} | {} | {} | +{}/-{} |\n",
+                    author.name,
+                    author.commit_count,
+                    author.co_authored_count,
+                    author.additions,
+                    author.deletions
+                ));
+            }
+            output.push('\n');
+        }
+
+        // Excluded section
+        if !data.excluded_stats.is_empty() {
+            output.push_str("## Excluded\n\n");
+            output.push_str("| Reason | Files | Lines |\n");
+            output.push_str("|--------|-------|-------|\n");
+            for excluded in &data.excluded_stats {
+                output.push_str(&format!(
+                    "| {} | {} | +{}/-{} |\n",
+                    excluded.reason, excluded.file_count, excluded.additions, excluded.deletions
+                ));
+            }
+            output.push('\n');
+        }
+
+        // Commits section, split per repository when combined
+        output.push_str("## Commits\n\n");
+        if data.repos.is_empty() {
+            push_commits(&mut output, &data.commits, verbose);
+        } else {
+            for repo in &data.repos {
+                output.push_str(&format!("### {}\n\n", repo.repo));
+                push_commits(&mut output, &repo.commits, verbose);
+                output.push('\n');
             }
         }
 
         output
     }
 }
+
+fn push_commits(output: &mut String, commits: &[Commit], verbose: bool) {
+    for commit in commits {
+        let date = format_timestamp(&commit.timestamp);
+        if verbose {
+            output.push_str(&format!(
+                "- `{}` `{}` {}\n",
+                date, commit.short_hash, commit.message
+            ));
+            for file in &commit.files_changed {
+                output.push_str(&format!(
+                    "  - `{}` (+{}/-{})\n",
+                    file.path, file.additions, file.deletions
+                ));
+            }
+        } else {
+            output.push_str(&format!("- `{}` {}\n", date, commit.message));
+        }
+    }
+}
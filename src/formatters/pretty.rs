@@ -1,7 +1,7 @@
 use colored::Colorize;
 
 use super::Formatter;
-use crate::git::GitSummaryData;
+use crate::git::{Commit, GitSummaryData};
 
 pub struct PrettyFormatter;
 
@@ -22,12 +22,22 @@ fn format_timestamp(iso: &str) -> String {
 
 impl Formatter for PrettyFormatter {
     fn format(&self, data: &GitSummaryData, summary: &str, verbose: bool, quiet: bool) -> String {
+        format!(
+            "{}{}{}",
+            self.header(data, quiet),
+            summary,
+            self.footer(data, verbose, quiet)
+        )
+    }
+}
+
+impl PrettyFormatter {
+    /// Everything before the summary, so that it can be streamed in place
+    pub fn header(&self, data: &GitSummaryData, quiet: bool) -> String {
         let mut output = String::new();
 
         if quiet {
             // Just the summary
-            output.push_str(summary);
-            output.push('\n');
             return output;
         }
 
@@ -36,10 +46,7 @@ impl Formatter for PrettyFormatter {
         output.push_str(&header_line.cyan().to_string());
         output.push('\n');
 
-        let title = format!(
-            "  Git Summary: {} ({})",
-            data.date_range, data.branch
-        );
+        let title = format!("  Git Summary: {} ({})", data.date_range, data.branch);
         output.push_str(&title.cyan().bold().to_string());
         output.push('\n');
 
@@ -57,8 +64,33 @@ impl Formatter for PrettyFormatter {
 
         // Summary section
         output.push_str(&"## Summary\n".yellow().bold().to_string());
-        output.push_str(summary);
-        output.push_str("\n\n");
+
+        output
+    }
+
+    /// Everything after the summary
+    pub fn footer(&self, data: &GitSummaryData, verbose: bool, quiet: bool) -> String {
+        if quiet {
+            return "\n".to_string();
+        }
+
+        let mut output = String::from("\n\n");
+
+        // By Repository section
+        if !data.repos.is_empty() {
+            output.push_str(&"## By Repository\n".yellow().bold().to_string());
+            for repo in &data.repos {
+                let line = format!(
+                    "  {:20} {:3} commits, {:>+5}/-{:<5} lines\n",
+                    repo.repo,
+                    repo.commits.len(),
+                    repo.total_additions,
+                    repo.total_deletions
+                );
+                output.push_str(&line);
+            }
+            output.push('\n');
+        }
 
         // By Area section
         if !data.area_stats.is_empty() {
@@ -73,34 +105,101 @@ impl Formatter for PrettyFormatter {
             output.push('\n');
         }
 
-        // Commits section
-        output.push_str(&"## Commits\n".yellow().bold().to_string());
-        for commit in &data.commits {
-            let date = format_timestamp(&commit.timestamp);
-            if verbose {
-                output.push_str(&format!(
-                    "  {} {} {}\n",
-                    date.dimmed(),
-                    commit.short_hash.dimmed(),
-                    commit.message
-                ));
-                for file in &commit.files_changed {
-                    output.push_str(&format!(
-                        "              {:>+4}/-{:<4} {}\n",
-                        file.additions,
-                        file.deletions,
-                        file.path.dimmed()
-                    ));
+        // By Type section (only when Conventional Commits are in use)
+        if data.commits.iter().any(|c| c.conventional.kind.is_some()) {
+            output.push_str(&"## By Type\n".yellow().bold().to_string());
+            for kind in &data.type_stats {
+                let mut line = format!(
+                    "  {:20} {:3} commits, {:>+5}/-{:<5} lines",
+                    kind.kind, kind.commit_count, kind.additions, kind.deletions
+                );
+                if kind.breaking_count > 0 {
+                    line.push_str(&format!(" ({} breaking)", kind.breaking_count));
                 }
-            } else {
-                output.push_str(&format!(
-                    "  {} {}\n",
-                    date.dimmed(),
-                    commit.message
-                ));
+                output.push_str(&line);
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+
+        // By Owner section
+        if !data.owner_stats.is_empty() {
+            output.push_str(&"## By Owner\n".yellow().bold().to_string());
+            for owner in &data.owner_stats {
+                let line = format!(
+                    "  {:20} {:3} commits, {:>+5}/-{:<5} lines\n",
+                    owner.owner, owner.commit_count, owner.additions, owner.deletions
+                );
+                output.push_str(&line);
+            }
+            output.push('\n');
+        }
+
+        // Contributors section
+        if !data.author_stats.is_empty() {
+            output.push_str(&"## Contributors\n".yellow().bold().to_string());
+            for author in &data.author_stats {
+                let mut line = format!(
+                    "  {:20} {:3} commits, {:>+5}/-{:<5} lines",
+                    author.name, author.commit_count, author.additions, author.deletions
+                );
+                if author.co_authored_count > 0 {
+                    line.push_str(&format!(" ({} co-authored)", author.co_authored_count));
+                }
+                output.push_str(&line);
+                output.push('\n');
+            }
+            output.push('\n');
+        }
+
+        // Excluded section
+        if !data.excluded_stats.is_empty() {
+            output.push_str(&"## Excluded\n".yellow().bold().to_string());
+            for excluded in &data.excluded_stats {
+                let line = format!(
+                    "  {:20} {:3} files,   {:>+5}/-{:<5} lines\n",
+                    excluded.reason, excluded.file_count, excluded.additions, excluded.deletions
+                );
+                output.push_str(&line.dimmed().to_string());
+            }
+            output.push('\n');
+        }
+
+        // Commits section, split per repository when combined
+        output.push_str(&"## Commits\n".yellow().bold().to_string());
+        if data.repos.is_empty() {
+            push_commits(&mut output, &data.commits, verbose);
+        } else {
+            for repo in &data.repos {
+                output.push_str(&format!("  {}\n", repo.repo.bold()));
+                push_commits(&mut output, &repo.commits, verbose);
             }
         }
 
         output
     }
 }
+
+fn push_commits(output: &mut String, commits: &[Commit], verbose: bool) {
+    for commit in commits {
+        let date = format_timestamp(&commit.timestamp);
+        if verbose {
+            output.push_str(&format!(
+                "  {} {} {}\n",
+                date.dimmed(),
+                commit.short_hash.dimmed(),
+                commit.message
+            ));
+            for file in &commit.files_changed {
+                output.push_str(&format!(
+                    "              {:>+4}/-{:<4} {}\n",
+                    file.additions,
+                    file.deletions,
+                    file.path.dimmed()
+                ));
+            }
+        } else {
+            output.push_str(&format!("  {} {}\n", date.dimmed(), commit.message));
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobMatcher};
+use serde::{Deserialize, Serialize};
+
+use super::workspace::Package;
+
+/// Maps a glob of paths to a named area, e.g. `crates/billing/**` -> "Billing"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AreaRule {
+    pub pattern: String,
+    pub name: String,
+}
+
+/// Decides which area a changed file belongs to. The first matching rule
+/// wins; otherwise the file goes to its innermost workspace package (when
+/// packages are given) or is grouped by its leading directories.
+pub struct AreaMapper {
+    depth: usize,
+    rules: Vec<(GlobMatcher, String)>,
+    packages: Vec<Package>,
+}
+
+impl AreaMapper {
+    pub fn new(depth: usize, rules: &[AreaRule], mut packages: Vec<Package>) -> Result<Self> {
+        let rules = rules
+            .iter()
+            .map(|rule| {
+                let glob = Glob::new(&rule.pattern)
+                    .with_context(|| format!("Invalid area pattern: {}", rule.pattern))?;
+                Ok((glob.compile_matcher(), rule.name.clone()))
+            })
+            .collect::<Result<_>>()?;
+
+        // Deepest paths first so nested packages win over their parents
+        packages.sort_by_key(|p| std::cmp::Reverse(p.path.len()));
+
+        Ok(Self {
+            depth: depth.max(1),
+            rules,
+            packages,
+        })
+    }
+
+    pub fn area_for(&self, path: &str) -> String {
+        self.rules
+            .iter()
+            .find(|(matcher, _)| matcher.is_match(path))
+            .map(|(_, name)| name.clone())
+            .or_else(|| {
+                self.packages
+                    .iter()
+                    .find(|p| {
+                        path.strip_prefix(p.path.as_str())
+                            .is_some_and(|rest| rest.starts_with('/'))
+                    })
+                    .map(|p| p.name.clone())
+            })
+            .unwrap_or_else(|| extract_area(path, self.depth))
+    }
+}
+
+/// Extract the "area" from a file path: its first `depth` directories, or
+/// "(root)" for top-level files
+fn extract_area(path: &str, depth: usize) -> String {
+    let parts: Vec<&str> = path.split('/').collect();
+    if parts.len() > 1 {
+        let dirs = &parts[..(parts.len() - 1).min(depth)];
+        format!("{}/", dirs.join("/"))
+    } else {
+        "(root)".to_string()
+    }
+}
@@ -0,0 +1,35 @@
+use anyhow::Result;
+use std::path::PathBuf;
+
+use super::{Commit, RevRange};
+
+/// Source of repository data for `GitRepo`.
+///
+/// Implementations either shell out to `git` or read the object database
+/// in-process; both must return the same commit data for the same query.
+pub trait GitBackend: Send + Sync {
+    /// Name of the currently checked out branch (or "HEAD" when detached)
+    fn current_branch(&self) -> Result<String>;
+
+    /// Root of the working tree, or `None` for a bare repository
+    fn workdir(&self) -> Result<Option<PathBuf>>;
+
+    /// Value of a git config key (e.g. "user.email"), if set
+    fn config_value(&self, key: &str) -> Result<Option<String>>;
+
+    /// Commits in `range`, newest first, with per-file line stats.
+    /// `since` and `until` filter on commit date using git's date syntax.
+    fn commits(
+        &self,
+        range: &RevRange,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<Commit>>;
+
+    /// Most recent tag (annotated or lightweight) reachable from `rev`, as
+    /// `git describe --tags` picks it
+    fn latest_tag(&self, rev: &str) -> Result<Option<String>>;
+
+    /// Names of all tags pointing at commits reachable from `rev`, sorted
+    fn tags(&self, rev: &str) -> Result<Vec<String>>;
+}
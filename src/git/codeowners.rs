@@ -0,0 +1,98 @@
+use anyhow::{Context, Result};
+use globset::GlobSet;
+use std::path::Path;
+
+use super::patterns::compile_gitignore_pattern;
+
+/// Locations GitHub checks for a CODEOWNERS file, in order
+const CODEOWNERS_PATHS: [&str; 3] = [".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+/// Path ownership rules parsed from a CODEOWNERS file
+pub struct CodeOwners {
+    rules: Vec<(GlobSet, Vec<String>)>,
+}
+
+impl CodeOwners {
+    /// Find and parse the CODEOWNERS file under the repository root
+    pub fn discover(repo_root: &Path) -> Result<Option<Self>> {
+        for relative in CODEOWNERS_PATHS {
+            let path = repo_root.join(relative);
+            if path.is_file() {
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                return Self::parse(&text).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut rules = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let Some(pattern) = fields.next() else {
+                continue;
+            };
+            let owners = fields
+                .take_while(|f| !f.starts_with('#'))
+                .map(String::from)
+                .collect();
+
+            let glob = compile_gitignore_pattern(pattern)
+                .with_context(|| format!("Invalid CODEOWNERS pattern: {}", pattern))?;
+            rules.push((glob, owners));
+        }
+        Ok(Self { rules })
+    }
+
+    /// Owners of a path; the last matching rule wins. An empty slice means
+    /// the path is explicitly or implicitly unowned.
+    pub fn owners_for(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|(glob, _)| glob.is_match(path))
+            .map(|(_, owners)| owners.as_slice())
+            .unwrap_or(&[])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CODEOWNERS: &str = "\
+# Default owners
+*       @core
+*.md    @docs-team  # inline comment
+docs/*  @docs-team @writers
+/apps/  @apps
+/apps/legacy/
+";
+
+    #[test]
+    fn last_matching_rule_wins() {
+        let owners = CodeOwners::parse(CODEOWNERS).unwrap();
+        assert_eq!(owners.owners_for("src/main.rs"), ["@core"]);
+        assert_eq!(owners.owners_for("src/README.md"), ["@docs-team"]);
+        assert_eq!(owners.owners_for("apps/web/index.ts"), ["@apps"]);
+    }
+
+    #[test]
+    fn directory_star_only_covers_direct_children() {
+        let owners = CodeOwners::parse(CODEOWNERS).unwrap();
+        assert_eq!(owners.owners_for("docs/a.txt"), ["@docs-team", "@writers"]);
+        assert_eq!(owners.owners_for("docs/sub/b.txt"), ["@core"]);
+    }
+
+    #[test]
+    fn rule_without_owners_unowns_paths() {
+        let owners = CodeOwners::parse(CODEOWNERS).unwrap();
+        assert!(owners.owners_for("apps/legacy/old.ts").is_empty());
+    }
+}
@@ -0,0 +1,306 @@
+use std::path::{Component, Path};
+
+use super::{conventional, AreaStats, AuthorStats, ExcludedStats, GitSummaryData, OwnerStats};
+
+impl GitSummaryData {
+    /// Merge the summaries of several repositories into one report.
+    ///
+    /// Totals, types, contributors, owners and excluded churn are aggregated
+    /// across repositories; areas are prefixed with their repository name,
+    /// which gains parent directories where two repositories share a
+    /// directory name. The individual summaries are kept in `repos` for
+    /// per-repository sections, including their next versions.
+    pub fn combine(mut summaries: Vec<GitSummaryData>) -> GitSummaryData {
+        if summaries.len() == 1 {
+            return summaries.remove(0);
+        }
+
+        let names = unique_names(&summaries);
+        for (summary, name) in summaries.iter_mut().zip(names) {
+            summary.repo = name;
+        }
+
+        let mut branches: Vec<&str> = summaries.iter().map(|s| s.branch.as_str()).collect();
+        branches.sort();
+        branches.dedup();
+
+        let mut commits: Vec<_> = summaries.iter().flat_map(|s| s.commits.clone()).collect();
+        // ISO 8601 timestamps in mixed offsets don't sort as strings
+        commits.sort_by_key(|c| {
+            std::cmp::Reverse(
+                chrono::DateTime::parse_from_rfc3339(&c.timestamp)
+                    .map(|t| t.timestamp())
+                    .unwrap_or_default(),
+            )
+        });
+
+        let area_stats = summaries
+            .iter()
+            .flat_map(|s| {
+                s.area_stats.iter().map(|area| AreaStats {
+                    path: format!("{}: {}", s.repo, area.path),
+                    ..area.clone()
+                })
+            })
+            .collect::<Vec<_>>();
+        let mut area_stats = area_stats;
+        area_stats.sort_by_key(|a| std::cmp::Reverse(a.commit_count));
+
+        let mut owners: std::collections::HashMap<String, OwnerStats> =
+            std::collections::HashMap::new();
+        for owner in summaries.iter().flat_map(|s| &s.owner_stats) {
+            let entry = owners
+                .entry(owner.owner.clone())
+                .or_insert_with(|| OwnerStats {
+                    commit_count: 0,
+                    additions: 0,
+                    deletions: 0,
+                    ..owner.clone()
+                });
+            entry.commit_count += owner.commit_count;
+            entry.additions += owner.additions;
+            entry.deletions += owner.deletions;
+        }
+        let mut owner_stats: Vec<OwnerStats> = owners.into_values().collect();
+        owner_stats.sort_by_key(|o| std::cmp::Reverse(o.commit_count));
+
+        let mut authors: std::collections::HashMap<String, AuthorStats> =
+            std::collections::HashMap::new();
+        for author in summaries.iter().flat_map(|s| &s.author_stats) {
+            let entry = authors
+                .entry(author.email.to_lowercase())
+                .or_insert_with(|| AuthorStats {
+                    commit_count: 0,
+                    co_authored_count: 0,
+                    additions: 0,
+                    deletions: 0,
+                    ..author.clone()
+                });
+            entry.commit_count += author.commit_count;
+            entry.co_authored_count += author.co_authored_count;
+            entry.additions += author.additions;
+            entry.deletions += author.deletions;
+        }
+        let mut author_stats: Vec<AuthorStats> = authors.into_values().collect();
+        author_stats.sort_by(|a, b| {
+            b.commit_count
+                .cmp(&a.commit_count)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+
+        let mut excluded: std::collections::BTreeMap<String, ExcludedStats> =
+            std::collections::BTreeMap::new();
+        for stats in summaries.iter().flat_map(|s| &s.excluded_stats) {
+            let entry = excluded
+                .entry(stats.reason.clone())
+                .or_insert_with(|| ExcludedStats {
+                    file_count: 0,
+                    additions: 0,
+                    deletions: 0,
+                    ..stats.clone()
+                });
+            entry.file_count += stats.file_count;
+            entry.additions += stats.additions;
+            entry.deletions += stats.deletions;
+        }
+
+        let type_stats = conventional::type_stats(&commits);
+
+        GitSummaryData {
+            repo: summaries
+                .iter()
+                .map(|s| s.repo.as_str())
+                .collect::<Vec<_>>()
+                .join(", "),
+            branch: branches.join(", "),
+            date_range: summaries
+                .first()
+                .map(|s| s.date_range.clone())
+                .unwrap_or_default(),
+            commits,
+            area_stats,
+            owner_stats,
+            type_stats,
+            author_stats,
+            excluded_stats: excluded.into_values().collect(),
+            total_additions: summaries.iter().map(|s| s.total_additions).sum(),
+            total_deletions: summaries.iter().map(|s| s.total_deletions).sum(),
+            repos: summaries,
+            // Each repository has its own
+            next_version: None,
+            root: None,
+        }
+    }
+}
+
+/// Repository names extended with as many parent directories of their
+/// working trees as it takes to tell them apart, e.g. "a/api" and "b/api"
+fn unique_names(summaries: &[GitSummaryData]) -> Vec<String> {
+    // Directory names innermost first
+    let segments: Vec<Vec<String>> = summaries
+        .iter()
+        .map(|s| s.root.as_deref().map(segments).unwrap_or_default())
+        .collect();
+    let name = |index: usize, depth: usize| {
+        let segments = &segments[index];
+        if segments.is_empty() {
+            return summaries[index].repo.clone();
+        }
+        let mut parts: Vec<&str> = segments[..depth.min(segments.len())]
+            .iter()
+            .map(String::as_str)
+            .collect();
+        parts.reverse();
+        parts.join("/")
+    };
+
+    let mut depths = vec![1; summaries.len()];
+    loop {
+        let names: Vec<String> = (0..summaries.len()).map(|i| name(i, depths[i])).collect();
+        let mut extended = false;
+        for (index, depth) in depths.iter_mut().enumerate() {
+            let collides = names
+                .iter()
+                .enumerate()
+                .any(|(other, name)| other != index && *name == names[index]);
+            if collides && *depth < segments[index].len() {
+                *depth += 1;
+                extended = true;
+            }
+        }
+        if !extended {
+            return names;
+        }
+    }
+}
+
+fn segments(path: &Path) -> Vec<String> {
+    path.components()
+        .rev()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::fake::{commit, FakeBackend};
+    use crate::git::{GitRepo, SummaryOptions};
+
+    fn summary(root: &str, backend: FakeBackend) -> GitSummaryData {
+        GitRepo::new(Box::new(backend.workdir(root)))
+            .collect_summary(&SummaryOptions {
+                branch: "HEAD".to_string(),
+                area_depth: 1,
+                default_excludes: true,
+                next_version: true,
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    fn web() -> FakeBackend {
+        FakeBackend::new(vec![
+            commit("w2", "feat: add login")
+                .at("2024-01-17T09:00:00+00:00")
+                .file("src/login.ts", 30, 2)
+                .file("package-lock.json", 400, 10),
+            commit("w1", "fix: typo")
+                .by("Bob", "bob@example.com")
+                .at("2024-01-15T09:00:00+00:00")
+                .file("src/app.ts", 1, 1),
+        ])
+        .tag("v2.0.0", "w1")
+    }
+
+    fn api() -> FakeBackend {
+        FakeBackend::new(vec![commit("a1", "fix: timeout")
+            .by("Bob", "BOB@example.com")
+            .at("2024-01-16T09:00:00+02:00")
+            .file("src/server.rs", 5, 3)
+            .file("Cargo.lock", 50, 50)])
+    }
+
+    #[test]
+    fn totals_and_contributors_are_combined() {
+        let data = GitSummaryData::combine(vec![
+            summary("/work/web", web()),
+            summary("/work/api", api()),
+        ]);
+
+        assert_eq!(data.repo, "web, api");
+        let hashes: Vec<&str> = data.commits.iter().map(|c| c.hash.as_str()).collect();
+        assert_eq!(hashes, ["w2", "a1", "w1"]);
+        assert_eq!((data.total_additions, data.total_deletions), (36, 6));
+
+        let authors: Vec<(&str, usize)> = data
+            .author_stats
+            .iter()
+            .map(|a| (a.name.as_str(), a.commit_count))
+            .collect();
+        assert_eq!(authors, [("Bob", 2), ("Alice", 1)]);
+
+        let types: Vec<(&str, usize)> = data
+            .type_stats
+            .iter()
+            .map(|t| (t.kind.as_str(), t.commit_count))
+            .collect();
+        assert_eq!(types, [("fix", 2), ("feat", 1)]);
+
+        let excluded = &data.excluded_stats[0];
+        assert_eq!(
+            (
+                excluded.reason.as_str(),
+                excluded.file_count,
+                excluded.additions
+            ),
+            ("lockfile", 2, 450)
+        );
+
+        let mut areas: Vec<&str> = data.area_stats.iter().map(|a| a.path.as_str()).collect();
+        areas.sort();
+        assert_eq!(areas, ["api: src/", "web: src/"]);
+    }
+
+    #[test]
+    fn next_versions_stay_with_their_repositories() {
+        let data = GitSummaryData::combine(vec![
+            summary("/work/web", web()),
+            summary("/work/api", api()),
+        ]);
+
+        assert!(data.next_version.is_none());
+        let next: Vec<&str> = data
+            .repos
+            .iter()
+            .map(|r| r.next_version.as_ref().unwrap().next.as_str())
+            .collect();
+        assert_eq!(next, ["v2.1.0", "v0.0.1"]);
+    }
+
+    #[test]
+    fn repositories_with_the_same_name_are_told_apart() {
+        let data = GitSummaryData::combine(vec![
+            summary("/src/a/api", api()),
+            summary("/src/b/api", api()),
+            summary("/src/web", web()),
+        ]);
+
+        let names: Vec<&str> = data.repos.iter().map(|r| r.repo.as_str()).collect();
+        assert_eq!(names, ["a/api", "b/api", "web"]);
+        let mut areas: Vec<&str> = data.area_stats.iter().map(|a| a.path.as_str()).collect();
+        areas.sort();
+        assert_eq!(areas, ["a/api: src/", "b/api: src/", "web: src/"]);
+    }
+
+    #[test]
+    fn a_single_repository_is_returned_as_is() {
+        let data = GitSummaryData::combine(vec![summary("/work/web", web())]);
+        assert_eq!(data.repo, "web");
+        assert!(data.repos.is_empty());
+        assert_eq!(data.next_version.unwrap().next, "v2.1.0");
+    }
+}
@@ -0,0 +1,163 @@
+use serde::Serialize;
+
+use super::{Commit, Trailer, TypeStats};
+
+/// Label in "By Type" for commits without a Conventional Commits header
+pub const UNTYPED: &str = "other";
+
+/// Conventional Commits fields of a commit: `type(scope)!: description`
+/// plus a `BREAKING CHANGE:` footer
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Conventional {
+    /// "feat", "fix", ... lowercased; `None` when the subject has no header
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub scope: Option<String>,
+    /// Subject without the header (the whole subject when there is none)
+    pub description: String,
+    /// Marked with `!` or a `BREAKING CHANGE:` footer
+    pub breaking: bool,
+    /// Explanation from the `BREAKING CHANGE:` footer
+    pub breaking_change: Option<String>,
+}
+
+/// Parse a subject line and the trailers of its body
+pub fn parse(subject: &str, trailers: &[Trailer]) -> Conventional {
+    let breaking_change = trailers
+        .iter()
+        .find(|t| t.key == "BREAKING CHANGE" || t.key == "BREAKING-CHANGE")
+        .map(|t| t.value.clone());
+
+    let mut conventional = match parse_header(subject.trim()) {
+        Some((kind, scope, bang, description)) => Conventional {
+            kind: Some(kind.to_lowercase()),
+            scope: scope.map(str::to_string),
+            description: description.to_string(),
+            breaking: bang,
+            breaking_change: None,
+        },
+        None => Conventional {
+            description: subject.trim().to_string(),
+            ..Default::default()
+        },
+    };
+    conventional.breaking |= breaking_change.is_some();
+    conventional.breaking_change = breaking_change;
+    conventional
+}
+
+/// Split `type(scope)!: description`; the type is a single word and the
+/// scope, when present, is non-empty
+fn parse_header(subject: &str) -> Option<(&str, Option<&str>, bool, &str)> {
+    let (prefix, description) = subject.split_once(':')?;
+    let description = description.trim();
+    let (prefix, bang) = match prefix.strip_suffix('!') {
+        Some(prefix) => (prefix, true),
+        None => (prefix, false),
+    };
+    let (kind, scope) = match prefix.split_once('(') {
+        Some((kind, scope)) => {
+            let scope = scope.strip_suffix(')')?.trim();
+            if scope.is_empty() {
+                return None;
+            }
+            (kind, Some(scope))
+        }
+        None => (prefix, None),
+    };
+
+    let is_word = !kind.is_empty() && kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+    (is_word && !description.is_empty()).then_some((kind, scope, bang, description))
+}
+
+/// Commit count and line totals per type, sorted by commit count descending
+pub(super) fn type_stats(commits: &[Commit]) -> Vec<TypeStats> {
+    let mut stats: Vec<TypeStats> = Vec::new();
+    for commit in commits {
+        let kind = commit.conventional.kind.as_deref().unwrap_or(UNTYPED);
+        let index = match stats.iter().position(|s| s.kind == kind) {
+            Some(index) => index,
+            None => {
+                stats.push(TypeStats {
+                    kind: kind.to_string(),
+                    commit_count: 0,
+                    breaking_count: 0,
+                    additions: 0,
+                    deletions: 0,
+                });
+                stats.len() - 1
+            }
+        };
+        let entry = &mut stats[index];
+        entry.commit_count += 1;
+        if commit.conventional.breaking {
+            entry.breaking_count += 1;
+        }
+        for file in &commit.files_changed {
+            entry.additions += file.additions;
+            entry.deletions += file.deletions;
+        }
+    }
+
+    // Untyped commits go last regardless of their count
+    stats.sort_by_key(|s| (s.kind == UNTYPED, std::cmp::Reverse(s.commit_count)));
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::trailers;
+
+    fn header(subject: &str) -> Conventional {
+        parse(subject, &[])
+    }
+
+    #[test]
+    fn type_scope_and_description() {
+        let c = header("Feat(api): add pagination");
+        assert_eq!(c.kind.as_deref(), Some("feat"));
+        assert_eq!(c.scope.as_deref(), Some("api"));
+        assert_eq!(c.description, "add pagination");
+        assert!(!c.breaking);
+
+        let c = header("fix: handle empty input");
+        assert_eq!(c.kind.as_deref(), Some("fix"));
+        assert_eq!(c.scope, None);
+    }
+
+    #[test]
+    fn bang_marks_breaking() {
+        let c = header("refactor(core)!: drop the v1 API");
+        assert_eq!(c.kind.as_deref(), Some("refactor"));
+        assert_eq!(c.scope.as_deref(), Some("core"));
+        assert!(c.breaking);
+        assert_eq!(c.breaking_change, None);
+    }
+
+    #[test]
+    fn breaking_change_footer() {
+        let body = "Longer explanation.\n\nBREAKING CHANGE: config files use TOML\nReviewed-by: Bob <bob@example.com>";
+        let c = parse("feat: new config", &trailers::parse(body));
+        assert!(c.breaking);
+        assert_eq!(c.breaking_change.as_deref(), Some("config files use TOML"));
+
+        let body = "BREAKING-CHANGE: flags renamed";
+        assert!(parse("chore: cleanup", &trailers::parse(body)).breaking);
+    }
+
+    #[test]
+    fn subjects_without_a_header() {
+        for subject in [
+            "Merge branch 'main' into feature",
+            "Update README: add install steps",
+            "fix():  empty scope",
+            "fix: ",
+            "WIP",
+        ] {
+            let c = header(subject);
+            assert_eq!(c.kind, None, "{}", subject);
+            assert_eq!(c.description, subject.trim(), "{}", subject);
+        }
+    }
+}
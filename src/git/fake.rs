@@ -0,0 +1,215 @@
+//! In-memory `GitBackend` and commit fixtures for tests
+
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use super::{conventional, trailers, Commit, FileChange, GitBackend, Person, RevRange};
+
+/// A commit by "Alice <alice@example.com>" on 2024-01-15 with no files;
+/// `message` is the subject, a blank line and an optional body
+pub fn commit(hash: &str, message: &str) -> Commit {
+    let (subject, body) = message.split_once("\n\n").unwrap_or((message, ""));
+    let trailers = trailers::parse(body);
+    let alice = Person {
+        name: "Alice".to_string(),
+        email: "alice@example.com".to_string(),
+    };
+
+    Commit {
+        hash: hash.to_string(),
+        short_hash: hash.chars().take(7).collect(),
+        message: subject.to_string(),
+        body: body.to_string(),
+        conventional: conventional::parse(subject, &trailers),
+        co_authors: trailers::co_authors(&trailers),
+        trailers,
+        author: alice.clone(),
+        committer: alice,
+        timestamp: "2024-01-15T10:00:00+00:00".to_string(),
+        commit_timestamp: "2024-01-15T10:00:00+00:00".to_string(),
+        parents: Vec::new(),
+        files_changed: Vec::new(),
+    }
+}
+
+/// Builder-style tweaks for fixtures
+impl Commit {
+    pub fn by(mut self, name: &str, email: &str) -> Self {
+        self.author = Person {
+            name: name.to_string(),
+            email: email.to_string(),
+        };
+        self
+    }
+
+    pub fn at(mut self, timestamp: &str) -> Self {
+        self.timestamp = timestamp.to_string();
+        self.commit_timestamp = timestamp.to_string();
+        self
+    }
+
+    pub fn file(mut self, path: &str, additions: i32, deletions: i32) -> Self {
+        self.files_changed.push(FileChange {
+            path: path.to_string(),
+            additions,
+            deletions,
+        });
+        self
+    }
+}
+
+/// A repository held in memory. Commits are given newest first and, unless
+/// they name parents, each one's parent is the next in the list.
+#[derive(Default)]
+pub struct FakeBackend {
+    commits: Vec<Commit>,
+    /// Tag name to commit hash
+    tags: Vec<(String, String)>,
+    config: HashMap<String, String>,
+    workdir: Option<PathBuf>,
+}
+
+impl FakeBackend {
+    pub fn new(mut commits: Vec<Commit>) -> Self {
+        let next: Vec<Option<String>> = commits
+            .iter()
+            .skip(1)
+            .map(|c| Some(c.hash.clone()))
+            .chain([None])
+            .collect();
+        for (commit, parent) in commits.iter_mut().zip(next) {
+            if commit.parents.is_empty() {
+                commit.parents.extend(parent);
+            }
+        }
+        Self {
+            commits,
+            ..Default::default()
+        }
+    }
+
+    pub fn tag(mut self, name: &str, hash: &str) -> Self {
+        self.tags.push((name.to_string(), hash.to_string()));
+        self
+    }
+
+    pub fn config(mut self, key: &str, value: &str) -> Self {
+        self.config.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    pub fn workdir(mut self, path: &str) -> Self {
+        self.workdir = Some(PathBuf::from(path));
+        self
+    }
+
+    /// Hash of "HEAD", a tag, a (prefix of a) hash, or any of those
+    /// followed by `^`
+    fn resolve(&self, rev: &str) -> Result<String> {
+        if let Some(child) = rev.strip_suffix('^') {
+            let child = self.resolve(child)?;
+            return self
+                .find(&child)
+                .and_then(|c| c.parents.first().cloned())
+                .with_context(|| format!("{} has no parent", rev));
+        }
+        if rev == "HEAD" {
+            return self
+                .commits
+                .first()
+                .map(|c| c.hash.clone())
+                .context("Empty repository");
+        }
+        if let Some((_, hash)) = self.tags.iter().find(|(name, _)| name == rev) {
+            return Ok(hash.clone());
+        }
+        self.commits
+            .iter()
+            .find(|c| c.hash.starts_with(rev))
+            .map(|c| c.hash.clone())
+            .with_context(|| format!("Unknown revision: {}", rev))
+    }
+
+    fn find(&self, hash: &str) -> Option<&Commit> {
+        self.commits.iter().find(|c| c.hash == hash)
+    }
+
+    /// Hashes of the commits reachable from `rev`
+    fn reachable(&self, rev: &str) -> Result<HashSet<String>> {
+        let mut seen = HashSet::new();
+        let mut pending = vec![self.resolve(rev)?];
+        while let Some(hash) = pending.pop() {
+            if seen.insert(hash.clone()) {
+                pending.extend(self.find(&hash).into_iter().flat_map(|c| c.parents.clone()));
+            }
+        }
+        Ok(seen)
+    }
+}
+
+impl GitBackend for FakeBackend {
+    fn current_branch(&self) -> Result<String> {
+        Ok("main".to_string())
+    }
+
+    fn workdir(&self) -> Result<Option<PathBuf>> {
+        Ok(self.workdir.clone())
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<String>> {
+        Ok(self.config.get(key).cloned())
+    }
+
+    /// `since` and `until` must be ISO 8601 dates, compared with the author
+    /// date
+    fn commits(
+        &self,
+        range: &RevRange,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<Commit>> {
+        let mut included = self.reachable(&range.to)?;
+        if let Some(ref from) = range.from {
+            let excluded = self.reachable(from)?;
+            if range.symmetric {
+                let common: HashSet<String> = included.intersection(&excluded).cloned().collect();
+                included.extend(excluded);
+                included.retain(|hash| !common.contains(hash));
+            } else {
+                included.retain(|hash| !excluded.contains(hash));
+            }
+        }
+
+        Ok(self
+            .commits
+            .iter()
+            .filter(|c| included.contains(&c.hash))
+            .filter(|c| since.is_none_or(|since| c.timestamp.as_str() >= since))
+            .filter(|c| until.is_none_or(|until| c.timestamp.as_str() < until))
+            .cloned()
+            .collect())
+    }
+
+    fn latest_tag(&self, rev: &str) -> Result<Option<String>> {
+        let reachable = self.reachable(rev)?;
+        Ok(self
+            .commits
+            .iter()
+            .filter(|c| reachable.contains(&c.hash))
+            .find_map(|c| self.tags.iter().find(|(_, hash)| *hash == c.hash))
+            .map(|(name, _)| name.clone()))
+    }
+
+    fn tags(&self, rev: &str) -> Result<Vec<String>> {
+        let reachable = self.reachable(rev)?;
+        let mut tags: Vec<String> = self
+            .tags
+            .iter()
+            .filter(|(_, hash)| reachable.contains(hash))
+            .map(|(name, _)| name.clone())
+            .collect();
+        tags.sort();
+        Ok(tags)
+    }
+}
@@ -0,0 +1,301 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::path::Path;
+
+use super::patterns::compile_gitattributes_pattern;
+
+/// Lockfiles excluded by default; their churn rarely reflects real work
+const DEFAULT_EXCLUDES: &[&str] = &[
+    "**/Cargo.lock",
+    "**/package-lock.json",
+    "**/npm-shrinkwrap.json",
+    "**/yarn.lock",
+    "**/pnpm-lock.yaml",
+    "**/bun.lockb",
+    "**/go.sum",
+    "**/Gemfile.lock",
+    "**/poetry.lock",
+    "**/Pipfile.lock",
+    "**/uv.lock",
+    "**/composer.lock",
+    "**/Podfile.lock",
+    "**/pubspec.lock",
+    "**/mix.lock",
+    "**/flake.lock",
+];
+
+/// Why a file's churn was left out of the stats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ExcludeReason {
+    /// Matched an `--exclude` pattern
+    Pattern,
+    /// Matched the built-in lockfile list
+    Lockfile,
+    /// Marked `linguist-generated` in .gitattributes
+    Generated,
+    /// Marked `linguist-vendored` in .gitattributes
+    Vendored,
+    /// Marked `-diff` or `binary` in .gitattributes
+    NoDiff,
+}
+
+impl ExcludeReason {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ExcludeReason::Pattern => "excluded",
+            ExcludeReason::Lockfile => "lockfile",
+            ExcludeReason::Generated => "generated",
+            ExcludeReason::Vendored => "vendored",
+            ExcludeReason::NoDiff => "no-diff",
+        }
+    }
+}
+
+/// Decides which changed files count toward the stats. `--include`
+/// patterns override every other reason to exclude a file.
+pub struct PathFilter {
+    includes: GlobSet,
+    excludes: GlobSet,
+    lockfiles: Option<GlobSet>,
+    attributes: Vec<AttributeRule>,
+}
+
+/// One `.gitattributes` line, reduced to the attributes we care about.
+/// `None` means the line does not mention the attribute.
+struct AttributeRule {
+    glob: GlobSet,
+    generated: Option<bool>,
+    vendored: Option<bool>,
+    diff: Option<bool>,
+}
+
+impl PathFilter {
+    pub fn new(
+        includes: &[String],
+        excludes: &[String],
+        default_excludes: bool,
+        repo_root: Option<&Path>,
+    ) -> Result<Self> {
+        let lockfiles = if default_excludes {
+            let patterns: Vec<String> = DEFAULT_EXCLUDES.iter().map(|p| p.to_string()).collect();
+            Some(glob_set(&patterns)?)
+        } else {
+            None
+        };
+
+        let attributes = match repo_root.map(|root| root.join(".gitattributes")) {
+            Some(path) if path.is_file() => {
+                let text = std::fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?;
+                parse_attributes(&text)?
+            }
+            _ => Vec::new(),
+        };
+
+        Ok(Self {
+            includes: glob_set(includes)?,
+            excludes: glob_set(excludes)?,
+            lockfiles,
+            attributes,
+        })
+    }
+
+    /// Why `path` should be left out of the stats, or `None` to keep it
+    pub fn exclude_reason(&self, path: &str) -> Option<ExcludeReason> {
+        if self.includes.is_match(path) {
+            return None;
+        }
+        if self.excludes.is_match(path) {
+            return Some(ExcludeReason::Pattern);
+        }
+        if self.lockfiles.as_ref().is_some_and(|l| l.is_match(path)) {
+            return Some(ExcludeReason::Lockfile);
+        }
+
+        // Later .gitattributes lines override earlier ones per attribute
+        let (mut generated, mut vendored, mut diff) = (false, false, true);
+        for rule in self.attributes.iter().filter(|r| r.glob.is_match(path)) {
+            generated = rule.generated.unwrap_or(generated);
+            vendored = rule.vendored.unwrap_or(vendored);
+            diff = rule.diff.unwrap_or(diff);
+        }
+
+        if generated {
+            Some(ExcludeReason::Generated)
+        } else if vendored {
+            Some(ExcludeReason::Vendored)
+        } else if !diff {
+            Some(ExcludeReason::NoDiff)
+        } else {
+            None
+        }
+    }
+}
+
+fn glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).with_context(|| format!("Invalid path glob: {}", pattern))?);
+    }
+    Ok(builder.build()?)
+}
+
+fn parse_attributes(text: &str) -> Result<Vec<AttributeRule>> {
+    let mut rules = Vec::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let Some(pattern) = fields.next() else {
+            continue;
+        };
+
+        let mut rule = AttributeRule {
+            glob: compile_gitattributes_pattern(pattern)
+                .with_context(|| format!("Invalid .gitattributes pattern: {}", pattern))?,
+            generated: None,
+            vendored: None,
+            diff: None,
+        };
+
+        for attr in fields {
+            let (name, set) = if let Some((name, value)) = attr.split_once('=') {
+                (name, value != "false")
+            } else if let Some(name) = attr.strip_prefix('-') {
+                (name, false)
+            } else if let Some(name) = attr.strip_prefix('!') {
+                // "Unspecified": back to the default, which is only set for diff
+                (name, name == "diff")
+            } else {
+                (attr, true)
+            };
+            match name {
+                "linguist-generated" => rule.generated = Some(set),
+                "linguist-vendored" => rule.vendored = Some(set),
+                "diff" => rule.diff = Some(set),
+                // The `binary` macro expands to `-diff -merge -text`
+                "binary" if set => rule.diff = Some(false),
+                _ => {}
+            }
+        }
+
+        if rule.generated.is_some() || rule.vendored.is_some() || rule.diff.is_some() {
+            rules.push(rule);
+        }
+    }
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    /// A filter for a repository whose .gitattributes holds `attributes`
+    fn filter(includes: &[&str], excludes: &[&str], attributes: &str) -> PathFilter {
+        let root = tempfile::tempdir().unwrap();
+        std::fs::write(root.path().join(".gitattributes"), attributes).unwrap();
+        PathFilter::new(
+            &strings(includes),
+            &strings(excludes),
+            true,
+            Some(root.path()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn lockfiles_are_excluded_by_default() {
+        let filter = filter(&[], &[], "");
+        assert_eq!(
+            filter.exclude_reason("Cargo.lock"),
+            Some(ExcludeReason::Lockfile)
+        );
+        assert_eq!(
+            filter.exclude_reason("web/package-lock.json"),
+            Some(ExcludeReason::Lockfile)
+        );
+        assert_eq!(filter.exclude_reason("src/lock.rs"), None);
+
+        let all = PathFilter::new(&[], &[], false, None).unwrap();
+        assert_eq!(all.exclude_reason("Cargo.lock"), None);
+    }
+
+    #[test]
+    fn include_overrides_every_exclusion() {
+        let filter = filter(
+            &["Cargo.lock", "gen/keep.rs"],
+            &["*.snap"],
+            "gen/** linguist-generated\n",
+        );
+        assert_eq!(filter.exclude_reason("Cargo.lock"), None);
+        assert_eq!(filter.exclude_reason("gen/keep.rs"), None);
+        assert_eq!(
+            filter.exclude_reason("gen/other.rs"),
+            Some(ExcludeReason::Generated)
+        );
+        assert_eq!(
+            filter.exclude_reason("tests/a.snap"),
+            Some(ExcludeReason::Pattern)
+        );
+    }
+
+    #[test]
+    fn linguist_attributes() {
+        let filter = filter(
+            &[],
+            &[],
+            "# Generated code\n\
+             *.pb.go linguist-generated=true\n\
+             vendor/** linguist-vendored\n\
+             vendor/ours/** linguist-vendored=false\n\
+             third_party linguist-vendored\n",
+        );
+        assert_eq!(
+            filter.exclude_reason("api/service.pb.go"),
+            Some(ExcludeReason::Generated)
+        );
+        assert_eq!(
+            filter.exclude_reason("vendor/lib/a.js"),
+            Some(ExcludeReason::Vendored)
+        );
+        assert_eq!(filter.exclude_reason("vendor/ours/b.js"), None);
+        // A bare directory name doesn't cover the files inside it
+        assert_eq!(filter.exclude_reason("third_party/c.js"), None);
+    }
+
+    #[test]
+    fn no_diff_attributes() {
+        let filter = filter(&[], &[], "*.svg -diff\n*.bin binary\n*.txt diff\n");
+        assert_eq!(
+            filter.exclude_reason("logo.svg"),
+            Some(ExcludeReason::NoDiff)
+        );
+        assert_eq!(
+            filter.exclude_reason("data/x.bin"),
+            Some(ExcludeReason::NoDiff)
+        );
+        assert_eq!(filter.exclude_reason("notes.txt"), None);
+    }
+
+    #[test]
+    fn unset_attributes_fall_back_to_the_default() {
+        let filter = filter(
+            &[],
+            &[],
+            "assets/** -diff linguist-generated\n\
+             assets/src/** !diff !linguist-generated\n",
+        );
+        assert_eq!(
+            filter.exclude_reason("assets/out/a.css"),
+            Some(ExcludeReason::Generated)
+        );
+        assert_eq!(filter.exclude_reason("assets/src/a.scss"), None);
+    }
+}
@@ -0,0 +1,186 @@
+use super::Person;
+
+/// Identity mapping in git's `.mailmap` format.
+///
+/// Supported line forms:
+///   Proper Name <commit@email>
+///   <proper@email> <commit@email>
+///   Proper Name <proper@email> <commit@email>
+///   Proper Name <proper@email> Commit Name <commit@email>
+#[derive(Debug, Default)]
+pub struct Mailmap {
+    entries: Vec<Entry>,
+}
+
+#[derive(Debug)]
+struct Entry {
+    proper_name: Option<String>,
+    proper_email: Option<String>,
+    commit_name: Option<String>,
+    commit_email: String,
+}
+
+impl Mailmap {
+    /// Add the entries from a mailmap file's contents. Entries added later
+    /// take precedence over earlier ones, as with git.
+    pub fn extend_from_str(&mut self, text: &str) {
+        self.entries.extend(text.lines().filter_map(parse_line));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Map an identity to its canonical form. Entries that name the commit
+    /// author win over email-only entries; emails and names match
+    /// case-insensitively.
+    pub fn resolve(&self, person: &Person) -> Person {
+        let matches_email = |e: &&Entry| e.commit_email.eq_ignore_ascii_case(&person.email);
+
+        let entry = self
+            .entries
+            .iter()
+            .rev()
+            .filter(matches_email)
+            .find(|e| {
+                e.commit_name
+                    .as_deref()
+                    .is_some_and(|name| name.eq_ignore_ascii_case(&person.name))
+            })
+            .or_else(|| {
+                self.entries
+                    .iter()
+                    .rev()
+                    .filter(matches_email)
+                    .find(|e| e.commit_name.is_none())
+            });
+
+        match entry {
+            Some(entry) => Person {
+                name: entry
+                    .proper_name
+                    .clone()
+                    .unwrap_or_else(|| person.name.clone()),
+                email: entry
+                    .proper_email
+                    .clone()
+                    .unwrap_or_else(|| person.email.clone()),
+            },
+            None => person.clone(),
+        }
+    }
+}
+
+fn parse_line(line: &str) -> Option<Entry> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let (first_name, first_email, rest) = split_identity(line)?;
+    match split_identity(rest) {
+        Some((commit_name, commit_email, _)) => Some(Entry {
+            proper_name: first_name,
+            proper_email: Some(first_email),
+            commit_name,
+            commit_email,
+        }),
+        None => Some(Entry {
+            proper_name: first_name,
+            proper_email: None,
+            commit_name: None,
+            commit_email: first_email,
+        }),
+    }
+}
+
+/// Split "Name <email> rest" into its optional name, email and remainder
+fn split_identity(text: &str) -> Option<(Option<String>, String, &str)> {
+    let open = text.find('<')?;
+    let close = open + text[open..].find('>')?;
+
+    let name = text[..open].trim();
+    let email = text[open + 1..close].trim();
+    let name = (!name.is_empty()).then(|| name.to_string());
+
+    Some((name, email.to_string(), &text[close + 1..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn person(name: &str, email: &str) -> Person {
+        Person {
+            name: name.to_string(),
+            email: email.to_string(),
+        }
+    }
+
+    fn mailmap(text: &str) -> Mailmap {
+        let mut mailmap = Mailmap::default();
+        mailmap.extend_from_str(text);
+        mailmap
+    }
+
+    #[test]
+    fn line_forms() {
+        let mailmap = mailmap(
+            "# comment\n\
+             Alice Smith <alice@old.example>\n\
+             <bob@example.com> <bob@laptop.local>\n\
+             Carol Jones <carol@example.com> <cj@old.example>\n\
+             Dan Brown <dan@example.com> danny <dan@old.example>\n",
+        );
+
+        let resolve = |name, email| mailmap.resolve(&person(name, email));
+        assert_eq!(
+            resolve("alice", "alice@old.example"),
+            person("Alice Smith", "alice@old.example")
+        );
+        assert_eq!(
+            resolve("Bob", "bob@laptop.local"),
+            person("Bob", "bob@example.com")
+        );
+        assert_eq!(
+            resolve("cj", "cj@old.example"),
+            person("Carol Jones", "carol@example.com")
+        );
+        assert_eq!(
+            resolve("danny", "dan@old.example"),
+            person("Dan Brown", "dan@example.com")
+        );
+        // The last form only applies to the named commit author
+        assert_eq!(
+            resolve("Daniel", "dan@old.example"),
+            person("Daniel", "dan@old.example")
+        );
+    }
+
+    #[test]
+    fn name_and_email_entries_win_over_email_only_ones() {
+        let mailmap = mailmap(
+            "Build Bot <ci@example.com> Jenkins <ci@example.com>\n\
+             Shared Account <shared@example.com> <ci@example.com>\n",
+        );
+
+        assert_eq!(
+            mailmap.resolve(&person("Jenkins", "ci@example.com")),
+            person("Build Bot", "ci@example.com")
+        );
+        assert_eq!(
+            mailmap.resolve(&person("Someone", "ci@example.com")),
+            person("Shared Account", "shared@example.com")
+        );
+    }
+
+    #[test]
+    fn emails_match_case_insensitively() {
+        let mailmap = mailmap("Alice Smith <alice@example.com> <Alice@Old.Example>");
+        assert_eq!(
+            mailmap.resolve(&person("alice", "ALICE@old.example")),
+            person("Alice Smith", "alice@example.com")
+        );
+        assert!(Mailmap::default().is_empty());
+    }
+}
@@ -0,0 +1,910 @@
+mod areas;
+mod backend;
+mod codeowners;
+mod combine;
+mod conventional;
+#[cfg(test)]
+pub(crate) mod fake;
+mod filter;
+mod mailmap;
+#[cfg(feature = "native")]
+mod native;
+mod patterns;
+mod remote;
+mod subprocess;
+mod trailers;
+mod version;
+mod workspace;
+
+pub use areas::{AreaMapper, AreaRule};
+pub use backend::GitBackend;
+pub use codeowners::CodeOwners;
+pub use conventional::Conventional;
+pub use filter::{ExcludeReason, PathFilter};
+pub use mailmap::Mailmap;
+#[cfg(feature = "native")]
+pub use native::NativeBackend;
+pub use subprocess::SubprocessBackend;
+pub use trailers::Trailer;
+pub use version::NextVersion;
+pub use workspace::Package;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Commit {
+    pub hash: String,
+    pub short_hash: String,
+    /// Subject line
+    pub message: String,
+    /// Everything after the subject, including trailers
+    pub body: String,
+    /// Type, scope and breaking marker parsed from the subject and footers
+    pub conventional: Conventional,
+    pub trailers: Vec<Trailer>,
+    pub author: Person,
+    /// People credited via `Co-authored-by:` trailers
+    pub co_authors: Vec<Person>,
+    pub committer: Person,
+    /// Author date (ISO 8601)
+    pub timestamp: String,
+    /// Committer date (ISO 8601)
+    pub commit_timestamp: String,
+    pub parents: Vec<String>,
+    pub files_changed: Vec<FileChange>,
+}
+
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, Hash)]
+pub struct Person {
+    pub name: String,
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileChange {
+    pub path: String,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AreaStats {
+    /// Directory prefix such as "src/", or the name of a matching area rule
+    pub path: String,
+    pub commit_count: usize,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnerStats {
+    /// Team or user from CODEOWNERS, or "(unowned)"
+    pub owner: String,
+    pub commit_count: usize,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+/// Commits per Conventional Commits type ("other" for untyped commits)
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeStats {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub commit_count: usize,
+    /// How many of `commit_count` are breaking changes
+    pub breaking_count: usize,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+/// Churn left out of the stats for one reason (lockfiles, generated code, ...)
+#[derive(Debug, Clone, Serialize)]
+pub struct ExcludedStats {
+    pub reason: String,
+    pub file_count: usize,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AuthorStats {
+    pub name: String,
+    pub email: String,
+    /// Commits authored or co-authored
+    pub commit_count: usize,
+    /// How many of `commit_count` were via a `Co-authored-by:` trailer
+    pub co_authored_count: usize,
+    pub additions: i32,
+    pub deletions: i32,
+}
+
+/// What the "By Area" section groups changed files by
+#[derive(Debug, Clone, Copy, Default)]
+pub enum AreaGrouping {
+    /// Leading directories of the path
+    #[default]
+    Directory,
+    /// The workspace package (crate, npm package, Go module) owning the file
+    Package,
+}
+
+/// How line counts of a co-authored commit are credited
+#[derive(Debug, Clone, Copy, Default)]
+pub enum CoAuthorCredit {
+    /// Every contributor is credited with all of the commit's lines
+    #[default]
+    Full,
+    /// Lines are divided evenly between the author and co-authors
+    Split,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GitSummaryData {
+    /// Repository name (its directory), or the list of names when combined
+    pub repo: String,
+    /// Working tree the data comes from; `None` for bare and combined
+    /// repositories
+    #[serde(skip)]
+    pub root: Option<PathBuf>,
+    pub branch: String,
+    pub date_range: String,
+    pub commits: Vec<Commit>,
+    pub area_stats: Vec<AreaStats>,
+    /// Empty unless grouping by CODEOWNERS was requested
+    pub owner_stats: Vec<OwnerStats>,
+    pub type_stats: Vec<TypeStats>,
+    pub author_stats: Vec<AuthorStats>,
+    /// Churn from excluded files, which is not part of the totals
+    pub excluded_stats: Vec<ExcludedStats>,
+    pub total_additions: i32,
+    pub total_deletions: i32,
+    /// Per-repository data when several repositories were combined
+    pub repos: Vec<GitSummaryData>,
+    /// Suggested next release, when requested
+    pub next_version: Option<NextVersion>,
+}
+
+/// Which commits to walk, in `git log` revision-range terms
+#[derive(Debug, Clone)]
+pub struct RevRange {
+    /// Commits reachable from here are left out
+    pub from: Option<String>,
+    pub to: String,
+    /// `from...to`: keep commits on either side, leaving out only their
+    /// common history
+    pub symmetric: bool,
+}
+
+impl RevRange {
+    /// Parse "B", "A..B" or "A...B"; an empty side means HEAD, as in git
+    pub fn parse(spec: &str) -> Self {
+        let or_head = |rev: &str| {
+            if rev.is_empty() {
+                "HEAD".to_string()
+            } else {
+                rev.to_string()
+            }
+        };
+        if let Some((from, to)) = spec.split_once("...") {
+            Self {
+                from: Some(or_head(from)),
+                to: or_head(to),
+                symmetric: true,
+            }
+        } else if let Some((from, to)) = spec.split_once("..") {
+            Self {
+                from: Some(or_head(from)),
+                to: or_head(to),
+                symmetric: false,
+            }
+        } else {
+            Self {
+                from: None,
+                to: spec.to_string(),
+                symmetric: false,
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for RevRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.from {
+            Some(ref from) if self.symmetric => write!(f, "{}...{}", from, self.to),
+            Some(ref from) => write!(f, "{}..{}", from, self.to),
+            None => write!(f, "{}", self.to),
+        }
+    }
+}
+
+/// Which commits `collect_summary` gathers
+#[derive(Debug, Clone, Default)]
+pub struct SummaryOptions {
+    /// Branch, or a revision range such as "v1.4.0..v1.5.0"
+    pub branch: String,
+    /// Leave out commits reachable from this ref
+    pub from: Option<String>,
+    /// Start after the most recent tag reachable from `branch`
+    pub since_last_tag: bool,
+    pub since: Option<String>,
+    pub until: Option<String>,
+    /// Description of the range for headers; derived from `since` and
+    /// `until` when unset
+    pub date_label: Option<String>,
+    /// Keep only commits whose author or a co-author has a name or email containing one of these
+    /// (case-insensitive). "me" stands for the configured `user.email`.
+    pub authors: Vec<String>,
+    /// Extra mailmap file applied on top of the repository's `.mailmap`
+    pub mailmap: Option<PathBuf>,
+    pub co_author_credit: CoAuthorCredit,
+    pub area_grouping: AreaGrouping,
+    /// Number of leading directories that make up an area
+    pub area_depth: usize,
+    /// Rules mapping path globs to named areas, checked before `area_depth`
+    pub area_rules: Vec<AreaRule>,
+    /// Also group changes by owning team from CODEOWNERS
+    pub group_by_owner: bool,
+    /// Path globs whose changes are left out of the stats
+    pub excludes: Vec<String>,
+    /// Path globs that are always counted, overriding every exclusion
+    pub includes: Vec<String>,
+    /// Exclude well-known lockfiles
+    pub default_excludes: bool,
+    /// Also suggest the next version for `branch`
+    pub next_version: bool,
+}
+
+/// Owner label for files no CODEOWNERS rule assigns
+const UNOWNED: &str = "(unowned)";
+
+pub struct GitRepo {
+    backend: Box<dyn GitBackend>,
+}
+
+impl GitRepo {
+    pub fn new(backend: Box<dyn GitBackend>) -> Self {
+        Self { backend }
+    }
+
+    pub fn collect_summary(&self, options: &SummaryOptions) -> Result<GitSummaryData> {
+        let mut range = RevRange::parse(&options.branch);
+        if let Some(ref from) = options.from {
+            range.from = Some(from.clone());
+        }
+        if options.since_last_tag {
+            let tag = self
+                .backend
+                .latest_tag(&range.to)?
+                .with_context(|| format!("No tag is reachable from {}", range.to))?;
+            range.from = Some(tag);
+        }
+        let since = options.since.as_deref();
+        let until = options.until.as_deref();
+
+        let mut commits = self.backend.commits(&range, since, until)?;
+
+        // Canonicalize identities before filtering so every alias of a
+        // person is matched and counted as one author
+        let mailmap = self.load_mailmap(options.mailmap.as_deref())?;
+        if !mailmap.is_empty() {
+            for commit in &mut commits {
+                commit.author = mailmap.resolve(&commit.author);
+                commit.committer = mailmap.resolve(&commit.committer);
+                for co_author in &mut commit.co_authors {
+                    *co_author = mailmap.resolve(co_author);
+                }
+            }
+        }
+
+        if !options.authors.is_empty() {
+            let patterns = self.resolve_author_patterns(&options.authors)?;
+            commits.retain(|commit| {
+                std::iter::once(&commit.author)
+                    .chain(&commit.co_authors)
+                    .any(|person| author_matches(person, &patterns))
+            });
+        }
+
+        // Set aside lockfile, generated and otherwise excluded churn so it
+        // is reported separately instead of skewing the stats
+        let filter = PathFilter::new(
+            &options.includes,
+            &options.excludes,
+            options.default_excludes,
+            self.backend.workdir()?.as_deref(),
+        )?;
+        let mut excluded: std::collections::BTreeMap<ExcludeReason, (usize, i32, i32)> =
+            std::collections::BTreeMap::new();
+        for commit in &mut commits {
+            commit
+                .files_changed
+                .retain(|file| match filter.exclude_reason(&file.path) {
+                    Some(reason) => {
+                        let entry = excluded.entry(reason).or_insert((0, 0, 0));
+                        entry.0 += 1;
+                        entry.1 += file.additions;
+                        entry.2 += file.deletions;
+                        false
+                    }
+                    None => true,
+                });
+        }
+        let excluded_stats = excluded
+            .into_iter()
+            .map(
+                |(reason, (file_count, additions, deletions))| ExcludedStats {
+                    reason: reason.label().to_string(),
+                    file_count,
+                    additions,
+                    deletions,
+                },
+            )
+            .collect();
+
+        let mut total_additions = 0;
+        let mut total_deletions = 0;
+        for file in commits.iter().flat_map(|c| &c.files_changed) {
+            total_additions += file.additions;
+            total_deletions += file.deletions;
+        }
+
+        // Calculate area stats (group by area rules, then packages or
+        // leading directories)
+        let packages = match options.area_grouping {
+            AreaGrouping::Directory => Vec::new(),
+            AreaGrouping::Package => self.load_packages()?,
+        };
+        let areas = AreaMapper::new(options.area_depth, &options.area_rules, packages)?;
+        let area_stats = group_changes(&commits, |file| vec![areas.area_for(&file.path)])
+            .into_iter()
+            .map(|(path, commit_count, additions, deletions)| AreaStats {
+                path,
+                commit_count,
+                additions,
+                deletions,
+            })
+            .collect();
+
+        // Calculate owner stats from CODEOWNERS (files with several owners
+        // count toward each of them)
+        let owner_stats = if options.group_by_owner {
+            let codeowners = self.load_codeowners()?;
+            group_changes(&commits, |file| match codeowners.owners_for(&file.path) {
+                [] => vec![UNOWNED.to_string()],
+                owners => owners.to_vec(),
+            })
+            .into_iter()
+            .map(|(owner, commit_count, additions, deletions)| OwnerStats {
+                owner,
+                commit_count,
+                additions,
+                deletions,
+            })
+            .collect()
+        } else {
+            Vec::new()
+        };
+
+        let type_stats = conventional::type_stats(&commits);
+        let author_stats = author_stats(&commits, options.co_author_credit);
+
+        // Build date range string
+        let date_range = match (&options.date_label, since, until) {
+            (Some(label), _, _) => label.clone(),
+            (None, Some(s), Some(u)) => format!("{} to {}", s, u),
+            (None, Some(s), None) => format!("since {}", s),
+            (None, None, Some(u)) => format!("until {}", u),
+            (None, None, None) => "all time".to_string(),
+        };
+
+        let next_version = if options.next_version {
+            Some(self.next_version(&range.to)?)
+        } else {
+            None
+        };
+
+        // Show the checked out branch rather than "HEAD"
+        if range.to == "HEAD" {
+            range.to = self
+                .backend
+                .current_branch()
+                .unwrap_or_else(|_| "HEAD".to_string());
+        }
+        let actual_branch = range.to_string();
+
+        let root = self.backend.workdir()?;
+        let repo = root
+            .as_deref()
+            .and_then(|root| root.file_name().map(|n| n.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "repository".to_string());
+
+        Ok(GitSummaryData {
+            repo,
+            root,
+            branch: actual_branch,
+            date_range,
+            commits,
+            area_stats,
+            owner_stats,
+            type_stats,
+            author_stats,
+            excluded_stats,
+            total_additions,
+            total_deletions,
+            repos: Vec::new(),
+            next_version,
+        })
+    }
+
+    fn load_packages(&self) -> Result<Vec<Package>> {
+        let root = self
+            .backend
+            .workdir()?
+            .context("Grouping by package requires a repository with a working tree")?;
+        let packages = workspace::detect_packages(&root)?;
+        if packages.is_empty() {
+            anyhow::bail!(
+                "No workspace packages found in {} (checked Cargo.toml, package.json, \
+                pnpm-workspace.yaml and go.work)",
+                root.display()
+            );
+        }
+        Ok(packages)
+    }
+
+    fn load_codeowners(&self) -> Result<CodeOwners> {
+        let root = self
+            .backend
+            .workdir()?
+            .context("Grouping by owner requires a repository with a working tree")?;
+        CodeOwners::discover(&root)?.with_context(|| {
+            format!(
+                "No CODEOWNERS file found in {} (checked .github/, the root and docs/)",
+                root.display()
+            )
+        })
+    }
+
+    /// Root of the working tree, if the repository has one
+    pub fn workdir(&self) -> Result<Option<PathBuf>> {
+        self.backend.workdir()
+    }
+
+    /// Browser URL of the `origin` remote, for linking to commits
+    pub fn web_url(&self) -> Result<Option<String>> {
+        Ok(self
+            .backend
+            .config_value("remote.origin.url")?
+            .and_then(|url| remote::web_url(&url)))
+    }
+
+    /// Tags reachable from `rev` that name a semantic version, highest first
+    pub fn version_tags(&self, rev: &str) -> Result<Vec<(String, semver::Version)>> {
+        let mut tags: Vec<(String, semver::Version)> = self
+            .backend
+            .tags(rev)?
+            .into_iter()
+            .filter_map(|tag| version::parse_tag(&tag).map(|version| (tag, version)))
+            .collect();
+        tags.sort_by(|a, b| b.1.cmp(&a.1));
+        Ok(tags)
+    }
+
+    /// Suggested next version for the commits on `rev` after its latest
+    /// version tag
+    pub fn next_version(&self, rev: &str) -> Result<NextVersion> {
+        let tags = self.version_tags(rev)?;
+        let current = tags.first();
+        let range = RevRange {
+            from: current.map(|(tag, _)| tag.clone()),
+            to: rev.to_string(),
+            symmetric: false,
+        };
+        let commits = self.backend.commits(&range, None, None)?;
+        Ok(NextVersion::new(
+            current.map(|(tag, version)| (tag.as_str(), version)),
+            &commits,
+        ))
+    }
+
+    /// Commits covered by release notes for `spec`: a range such as
+    /// "v1.4.0..v1.5.0" as given, a single ref from the tag before it, or
+    /// by default everything after the latest tag. Without an earlier tag
+    /// the range reaches back to the first commit.
+    pub fn release_range(&self, spec: Option<&str>) -> RevRange {
+        let spec = spec.unwrap_or("HEAD");
+        let mut range = RevRange::parse(spec);
+        if range.from.is_none() {
+            // The tag on `to` itself belongs to this release, so start
+            // looking from its parent. A root commit has none, and a bad
+            // ref is reported when the commits are read.
+            let base = if spec == "HEAD" {
+                spec.to_string()
+            } else {
+                format!("{}^", range.to)
+            };
+            range.from = self.backend.latest_tag(&base).unwrap_or(None);
+        }
+        range
+    }
+
+    /// Build the identity map from the repository's `.mailmap`, the
+    /// `mailmap.file` config and an optional extra file, in increasing
+    /// order of precedence
+    fn load_mailmap(&self, extra: Option<&Path>) -> Result<Mailmap> {
+        let mut mailmap = Mailmap::default();
+
+        let mut files = Vec::new();
+        if let Some(root) = self.backend.workdir()? {
+            files.push(root.join(".mailmap"));
+        }
+        if let Some(file) = self.backend.config_value("mailmap.file")? {
+            files.push(PathBuf::from(file));
+        }
+
+        // Repository-provided files are optional
+        for file in files {
+            if let Ok(text) = std::fs::read_to_string(&file) {
+                mailmap.extend_from_str(&text);
+            }
+        }
+
+        if let Some(extra) = extra {
+            let text = std::fs::read_to_string(extra)
+                .with_context(|| format!("Failed to read mailmap {}", extra.display()))?;
+            mailmap.extend_from_str(&text);
+        }
+
+        Ok(mailmap)
+    }
+
+    /// Lowercase the `--author` patterns, expanding "me" to `user.email`
+    fn resolve_author_patterns(&self, authors: &[String]) -> Result<Vec<String>> {
+        authors
+            .iter()
+            .map(|author| {
+                if author == "me" {
+                    self.backend
+                        .config_value("user.email")?
+                        .map(|email| email.to_lowercase())
+                        .context("--author me requires user.email to be set in git config")
+                } else {
+                    Ok(author.to_lowercase())
+                }
+            })
+            .collect()
+    }
+}
+
+/// List the git repositories directly inside `parent`, sorted by path
+pub fn discover_repos(parent: &Path) -> Result<Vec<PathBuf>> {
+    let entries = std::fs::read_dir(parent)
+        .with_context(|| format!("Failed to read directory {}", parent.display()))?;
+
+    let mut repos: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir() && path.join(".git").exists())
+        .collect();
+    repos.sort();
+    Ok(repos)
+}
+
+/// Commit count and line totals per group, sorted by commit count
+/// descending. `keys` names the groups a changed file belongs to; a commit
+/// counts once toward each group it touches.
+fn group_changes(
+    commits: &[Commit],
+    keys: impl Fn(&FileChange) -> Vec<String>,
+) -> Vec<(String, usize, i32, i32)> {
+    let mut group_map: std::collections::HashMap<String, (usize, i32, i32)> =
+        std::collections::HashMap::new();
+
+    for commit in commits {
+        // Track which groups this commit touched (for commit count)
+        let mut commit_groups: std::collections::HashSet<String> = std::collections::HashSet::new();
+
+        for file in &commit.files_changed {
+            for key in keys(file) {
+                let entry = group_map.entry(key.clone()).or_insert((0, 0, 0));
+                entry.1 += file.additions;
+                entry.2 += file.deletions;
+                commit_groups.insert(key);
+            }
+        }
+
+        // Increment commit count for each group touched
+        for key in commit_groups {
+            let entry = group_map.entry(key).or_insert((0, 0, 0));
+            entry.0 += 1;
+        }
+    }
+
+    let mut groups: Vec<(String, usize, i32, i32)> = group_map
+        .into_iter()
+        .map(|(key, (commit_count, additions, deletions))| {
+            (key, commit_count, additions, deletions)
+        })
+        .collect();
+
+    // Sort by commit count descending
+    groups.sort_by_key(|g| std::cmp::Reverse(g.1));
+    groups
+}
+
+fn author_matches(author: &Person, patterns: &[String]) -> bool {
+    let name = author.name.to_lowercase();
+    let email = author.email.to_lowercase();
+    patterns
+        .iter()
+        .any(|p| name.contains(p.as_str()) || email.contains(p.as_str()))
+}
+
+/// Per-contributor commit and line counts, keyed by email. Co-authors are
+/// credited alongside the author; one person listed twice on a commit is
+/// only counted once.
+fn author_stats(commits: &[Commit], credit: CoAuthorCredit) -> Vec<AuthorStats> {
+    let mut author_map: std::collections::HashMap<String, AuthorStats> =
+        std::collections::HashMap::new();
+
+    for commit in commits {
+        let additions: i32 = commit.files_changed.iter().map(|f| f.additions).sum();
+        let deletions: i32 = commit.files_changed.iter().map(|f| f.deletions).sum();
+
+        let mut contributors = vec![&commit.author];
+        for co_author in &commit.co_authors {
+            if !contributors
+                .iter()
+                .any(|p| p.email.eq_ignore_ascii_case(&co_author.email))
+            {
+                contributors.push(co_author);
+            }
+        }
+
+        let share = contributors.len() as i32;
+        for (i, person) in contributors.into_iter().enumerate() {
+            let (person_additions, person_deletions) = match credit {
+                CoAuthorCredit::Full => (additions, deletions),
+                // The author absorbs any remainder so totals still add up
+                CoAuthorCredit::Split if i == 0 => (
+                    additions / share + additions % share,
+                    deletions / share + deletions % share,
+                ),
+                CoAuthorCredit::Split => (additions / share, deletions / share),
+            };
+
+            let entry = author_map
+                .entry(person.email.to_lowercase())
+                .or_insert_with(|| AuthorStats {
+                    name: person.name.clone(),
+                    email: person.email.clone(),
+                    commit_count: 0,
+                    co_authored_count: 0,
+                    additions: 0,
+                    deletions: 0,
+                });
+
+            entry.commit_count += 1;
+            if i > 0 {
+                entry.co_authored_count += 1;
+            }
+            entry.additions += person_additions;
+            entry.deletions += person_deletions;
+        }
+    }
+
+    let mut stats: Vec<AuthorStats> = author_map.into_values().collect();
+    stats.sort_by(|a, b| {
+        b.commit_count
+            .cmp(&a.commit_count)
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fake::{commit, FakeBackend};
+    use super::*;
+
+    fn repo() -> GitRepo {
+        GitRepo::new(Box::new(
+            FakeBackend::new(vec![
+                commit(
+                    "c4",
+                    "feat: add export\n\nCo-authored-by: Bob <bob@example.com>",
+                )
+                .file("src/export.rs", 40, 0)
+                .file("Cargo.lock", 100, 20),
+                commit("c3", "fix(cli): handle empty input")
+                    .by("Bob", "bob@example.com")
+                    .file("src/cli.rs", 3, 1),
+                commit("c2", "docs: describe flags").file("README.md", 10, 2),
+                commit("c1", "initial commit").file("src/main.rs", 100, 0),
+            ])
+            .tag("v0.1.0", "c2"),
+        ))
+    }
+
+    fn options(branch: &str) -> SummaryOptions {
+        SummaryOptions {
+            branch: branch.to_string(),
+            area_depth: 1,
+            default_excludes: true,
+            ..Default::default()
+        }
+    }
+
+    fn hashes(data: &GitSummaryData) -> Vec<&str> {
+        data.commits.iter().map(|c| c.hash.as_str()).collect()
+    }
+
+    #[test]
+    fn rev_range_parse() {
+        let range = RevRange::parse("v1.0..");
+        assert_eq!(range.from.as_deref(), Some("v1.0"));
+        assert_eq!(range.to, "HEAD");
+        assert!(!range.symmetric);
+        assert!(RevRange::parse("a...b").symmetric);
+        assert_eq!(RevRange::parse("main").from, None);
+    }
+
+    #[test]
+    fn summary_of_whole_branch() {
+        let data = repo().collect_summary(&options("HEAD")).unwrap();
+        assert_eq!(hashes(&data), ["c4", "c3", "c2", "c1"]);
+        assert_eq!(data.branch, "main");
+        // Cargo.lock is excluded by default and reported separately
+        assert_eq!(data.total_additions, 153);
+        assert_eq!(data.excluded_stats.len(), 1);
+        assert_eq!(data.excluded_stats[0].additions, 100);
+
+        let src = data.area_stats.iter().find(|a| a.path == "src/").unwrap();
+        assert_eq!(src.commit_count, 3);
+    }
+
+    #[test]
+    fn range_since_tag() {
+        let data = repo().collect_summary(&options("v0.1.0..HEAD")).unwrap();
+        assert_eq!(hashes(&data), ["c4", "c3"]);
+
+        let data = repo()
+            .collect_summary(&SummaryOptions {
+                since_last_tag: true,
+                ..options("HEAD")
+            })
+            .unwrap();
+        assert_eq!(hashes(&data), ["c4", "c3"]);
+    }
+
+    #[test]
+    fn author_filter_includes_co_authored_commits() {
+        let data = repo()
+            .collect_summary(&SummaryOptions {
+                authors: vec!["bob".to_string()],
+                ..options("HEAD")
+            })
+            .unwrap();
+        assert_eq!(hashes(&data), ["c4", "c3"]);
+
+        let bob = data.author_stats.iter().find(|a| a.name == "Bob").unwrap();
+        assert_eq!(bob.commit_count, 2);
+        assert_eq!(bob.co_authored_count, 1);
+    }
+
+    #[test]
+    fn author_me_is_the_configured_email() {
+        let repo = GitRepo::new(Box::new(
+            FakeBackend::new(vec![
+                commit("c2", "mine").by("Carol", "carol@example.com"),
+                commit("c1", "theirs"),
+            ])
+            .config("user.email", "carol@example.com"),
+        ));
+        let data = repo
+            .collect_summary(&SummaryOptions {
+                authors: vec!["me".to_string()],
+                ..options("HEAD")
+            })
+            .unwrap();
+        assert_eq!(hashes(&data), ["c2"]);
+    }
+
+    #[test]
+    fn date_bounds_are_passed_to_the_backend() {
+        let repo = GitRepo::new(Box::new(FakeBackend::new(vec![
+            commit("c3", "late").at("2024-03-01T09:00:00+00:00"),
+            commit("c2", "middle").at("2024-02-01T09:00:00+00:00"),
+            commit("c1", "early").at("2024-01-01T09:00:00+00:00"),
+        ])));
+        let data = repo
+            .collect_summary(&SummaryOptions {
+                since: Some("2024-01-15".to_string()),
+                until: Some("2024-02-15".to_string()),
+                ..options("HEAD")
+            })
+            .unwrap();
+        assert_eq!(hashes(&data), ["c2"]);
+    }
+
+    #[test]
+    fn excluded_churn_is_reported_separately() {
+        let data = repo()
+            .collect_summary(&SummaryOptions {
+                excludes: vec!["README.md".to_string()],
+                ..options("HEAD")
+            })
+            .unwrap();
+
+        // Excluded files leave the totals and areas, but every commit stays
+        assert_eq!(hashes(&data), ["c4", "c3", "c2", "c1"]);
+        assert_eq!(data.total_additions, 143);
+        assert!(data.area_stats.iter().all(|a| a.path != "(root)"));
+
+        let excluded: Vec<(&str, usize, i32, i32)> = data
+            .excluded_stats
+            .iter()
+            .map(|e| (e.reason.as_str(), e.file_count, e.additions, e.deletions))
+            .collect();
+        assert_eq!(excluded, [("excluded", 1, 10, 2), ("lockfile", 1, 100, 20)]);
+    }
+
+    #[test]
+    fn mailmap_aliases_collapse_into_one_author() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(".mailmap"),
+            "Alice Smith <alice@example.com> <alice@laptop.local>\n",
+        )
+        .unwrap();
+        let backend = FakeBackend::new(vec![
+            commit("c2", "fix: retry").by("alice", "ALICE@laptop.local"),
+            commit("c1", "feat: start").by("Alice Smith", "alice@example.com"),
+        ])
+        .workdir(dir.path().to_str().unwrap());
+
+        let data = GitRepo::new(Box::new(backend))
+            .collect_summary(&options("HEAD"))
+            .unwrap();
+        let authors: Vec<(&str, &str, usize)> = data
+            .author_stats
+            .iter()
+            .map(|a| (a.name.as_str(), a.email.as_str(), a.commit_count))
+            .collect();
+        assert_eq!(authors, [("Alice Smith", "alice@example.com", 2)]);
+    }
+
+    #[test]
+    fn type_stats_count_conventional_types() {
+        let data = repo().collect_summary(&options("HEAD")).unwrap();
+        let kinds: Vec<(&str, usize)> = data
+            .type_stats
+            .iter()
+            .map(|t| (t.kind.as_str(), t.commit_count))
+            .collect();
+        assert!(kinds.contains(&("feat", 1)));
+        assert!(kinds.contains(&("fix", 1)));
+        assert_eq!(kinds.last(), Some(&("other", 1)));
+    }
+
+    #[test]
+    fn next_version_from_latest_tag() {
+        let next = repo().next_version("HEAD").unwrap();
+        assert_eq!(next.current.as_deref(), Some("v0.1.0"));
+        assert_eq!(next.next, "v0.2.0");
+        assert_eq!(next.commit_count, 2);
+    }
+
+    #[test]
+    fn release_range_starts_at_previous_tag() {
+        let range = repo().release_range(None);
+        assert_eq!(range.from.as_deref(), Some("v0.1.0"));
+
+        // Notes for the tagged release itself start at the tag before it
+        let range = repo().release_range(Some("v0.1.0"));
+        assert_eq!(range.from, None);
+        assert_eq!(range.to, "v0.1.0");
+    }
+}
@@ -0,0 +1,320 @@
+use anyhow::{Context, Result};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeZone};
+use gix::bstr::ByteSlice;
+use gix::date::time::format::ISO8601_STRICT;
+use gix::revision::walk::Sorting;
+use gix::traverse::commit::simple::CommitTimeOrder;
+use std::path::PathBuf;
+
+use super::{conventional, trailers, Commit, FileChange, GitBackend, Person, RevRange};
+
+/// Backend that reads the repository in-process via `gix`, without
+/// requiring a `git` binary on the host.
+pub struct NativeBackend {
+    repo: gix::ThreadSafeRepository,
+}
+
+impl NativeBackend {
+    pub fn open(path: &str) -> Result<Self> {
+        let repo = gix::ThreadSafeRepository::discover(path)
+            .with_context(|| format!("Failed to open git repository at {}", path))?;
+        Ok(Self { repo })
+    }
+}
+
+impl GitBackend for NativeBackend {
+    fn current_branch(&self) -> Result<String> {
+        let repo = self.repo.to_thread_local();
+        let head = repo.head_name().context("Failed to read HEAD")?;
+        Ok(head
+            .map(|name| name.shorten().to_string())
+            .unwrap_or_else(|| "HEAD".to_string()))
+    }
+
+    fn workdir(&self) -> Result<Option<PathBuf>> {
+        Ok(self.repo.work_dir().map(|path| path.to_path_buf()))
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<String>> {
+        let repo = self.repo.to_thread_local();
+        let value = repo.config_snapshot().string(key);
+        Ok(value.map(|v| v.to_str_lossy().into_owned()))
+    }
+
+    fn commits(
+        &self,
+        range: &RevRange,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<Commit>> {
+        let repo = self.repo.to_thread_local();
+        let since = since.map(parse_date).transpose()?;
+        let until = until.map(parse_date).transpose()?;
+
+        // Annotated tags resolve to tag objects; walks need their commits
+        let resolve = |rev: &str| -> Result<gix::ObjectId> {
+            let object = repo
+                .rev_parse_single(rev)
+                .with_context(|| format!("Failed to resolve {}", rev))?
+                .object()
+                .context("Failed to read object")?;
+            let commit = object
+                .peel_to_commit()
+                .with_context(|| format!("{} is not a commit", rev))?;
+            Ok(commit.id)
+        };
+        let to = resolve(&range.to)?;
+
+        // `from..to` hides everything reachable from `from`; `from...to`
+        // walks both sides and hides only their common history
+        let (tips, hidden) = match range.from.as_deref() {
+            None => (vec![to], Vec::new()),
+            Some(from) if range.symmetric => {
+                let from = resolve(from)?;
+                let base = repo.merge_base(from, to).ok().map(|id| id.detach());
+                (vec![to, from], base.into_iter().collect())
+            }
+            Some(from) => (vec![to], vec![resolve(from)?]),
+        };
+
+        // Mirror `git log --since`: walk newest first and stop once every
+        // queued commit is older than the cutoff.
+        let sorting = match since {
+            Some(seconds) => Sorting::ByCommitTimeCutoff {
+                order: CommitTimeOrder::NewestFirst,
+                seconds,
+            },
+            None => Sorting::ByCommitTime(CommitTimeOrder::NewestFirst),
+        };
+
+        let walk = repo
+            .rev_walk(tips)
+            .with_hidden(hidden)
+            .sorting(sorting)
+            .all()
+            .context("Failed to walk commit history")?;
+
+        let mut resource_cache = repo
+            .diff_resource_cache_for_tree_diff()
+            .context("Failed to prepare diff")?;
+
+        let mut commits = Vec::new();
+        for info in walk {
+            let info = info.context("Failed to walk commit history")?;
+            if until.is_some_and(|until| info.commit_time() > until) {
+                continue;
+            }
+
+            let commit = info.object().context("Failed to read commit")?;
+            let message = commit.message().context("Failed to decode commit")?;
+            let author = commit.author().context("Failed to decode commit")?;
+            let committer = commit.committer().context("Failed to decode commit")?;
+            let author_time = author.time().context("Failed to parse author date")?;
+            let commit_time = committer.time().context("Failed to parse commit date")?;
+
+            // Like `git log --numstat`, merges carry no file stats.
+            let parents: Vec<_> = commit.parent_ids().collect();
+            let files_changed = if parents.len() > 1 {
+                Vec::new()
+            } else {
+                let parent_tree = match parents.first() {
+                    Some(id) => id
+                        .object()
+                        .context("Failed to read parent commit")?
+                        .into_commit()
+                        .tree()?,
+                    None => repo.empty_tree(),
+                };
+                diff_trees(&parent_tree, &commit.tree()?, &mut resource_cache)?
+            };
+
+            let body = message
+                .body
+                .map(|body| body.to_str_lossy().trim().to_string())
+                .unwrap_or_default();
+            let trailers = trailers::parse(&body);
+
+            let subject = message.summary().to_str_lossy().into_owned();
+            commits.push(Commit {
+                hash: commit.id.to_string(),
+                short_hash: commit.short_id()?.to_string(),
+                conventional: conventional::parse(&subject, &trailers),
+                message: subject,
+                body,
+                author: to_person(author),
+                co_authors: trailers::co_authors(&trailers),
+                trailers,
+                committer: to_person(committer),
+                timestamp: author_time.format(ISO8601_STRICT),
+                commit_timestamp: commit_time.format(ISO8601_STRICT),
+                parents: parents.iter().map(|id| id.to_string()).collect(),
+                files_changed,
+            });
+        }
+
+        Ok(commits)
+    }
+
+    fn latest_tag(&self, rev: &str) -> Result<Option<String>> {
+        let repo = self.repo.to_thread_local();
+        let commit = repo
+            .rev_parse_single(rev)
+            .with_context(|| format!("Failed to resolve {}", rev))?
+            .object()
+            .context("Failed to read object")?
+            .peel_to_commit()
+            .with_context(|| format!("{} is not a commit", rev))?;
+
+        let resolution = commit
+            .describe()
+            .names(gix::commit::describe::SelectRef::AllTags)
+            .try_resolve()
+            .context("Failed to look up tags")?;
+        Ok(resolution
+            .and_then(|r| r.outcome.name)
+            .map(|name| name.to_str_lossy().into_owned()))
+    }
+
+    fn tags(&self, rev: &str) -> Result<Vec<String>> {
+        let repo = self.repo.to_thread_local();
+        let tip = repo
+            .rev_parse_single(rev)
+            .with_context(|| format!("Failed to resolve {}", rev))?
+            .object()
+            .context("Failed to read object")?
+            .peel_to_commit()
+            .with_context(|| format!("{} is not a commit", rev))?
+            .id;
+        let reachable = repo
+            .rev_walk([tip])
+            .all()
+            .context("Failed to walk commit history")?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<std::collections::HashSet<_>, _>>()
+            .context("Failed to walk commit history")?;
+
+        let references = repo.references().context("Failed to read references")?;
+        let mut tags = Vec::new();
+        for reference in references
+            .tags()
+            .map_err(|e| anyhow::anyhow!("Failed to read tags: {}", e))?
+        {
+            let mut reference =
+                reference.map_err(|e| anyhow::anyhow!("Failed to read tag: {}", e))?;
+            // Tags of trees or blobs can't be reachable from a commit
+            let Ok(id) = reference.peel_to_id() else {
+                continue;
+            };
+            if reachable.contains(&id.detach()) {
+                tags.push(reference.name().shorten().to_string());
+            }
+        }
+        tags.sort();
+        Ok(tags)
+    }
+}
+
+fn to_person(signature: gix::actor::SignatureRef<'_>) -> Person {
+    let signature = signature.trim();
+    Person {
+        name: signature.name.to_str_lossy().into_owned(),
+        email: signature.email.to_str_lossy().into_owned(),
+    }
+}
+
+/// Compute per-file line counts between two trees
+fn diff_trees(
+    old: &gix::Tree<'_>,
+    new: &gix::Tree<'_>,
+    resource_cache: &mut gix::diff::blob::Platform,
+) -> Result<Vec<FileChange>> {
+    let mut changes = Vec::new();
+    old.changes()?
+        .for_each_to_obtain_tree(new, |change| {
+            if change.entry_mode().is_tree() {
+                return Ok::<_, std::convert::Infallible>(
+                    gix::object::tree::diff::Action::Continue,
+                );
+            }
+
+            // Binary files have no line counts, matching numstat's "-"
+            let counts = change
+                .diff(resource_cache)
+                .ok()
+                .and_then(|mut platform| platform.line_counts().ok())
+                .flatten();
+
+            changes.push(FileChange {
+                path: change.location().to_str_lossy().into_owned(),
+                additions: counts.as_ref().map_or(0, |c| c.insertions as i32),
+                deletions: counts.as_ref().map_or(0, |c| c.removals as i32),
+            });
+            resource_cache.clear_resource_cache_keep_allocation();
+            Ok(gix::object::tree::diff::Action::Continue)
+        })
+        .context("Failed to diff commit")?;
+
+    // Match numstat's path ordering
+    changes.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(changes)
+}
+
+/// Parse a `--since`/`--until` value into seconds since the epoch.
+///
+/// Accepts "YYYY-MM-DD HH:MM:SS" and "YYYY-MM-DD" in local time, and
+/// otherwise anything `gix` understands: the "YYYY-MM-DD HH:MM:SS +ZZZZ"
+/// form `DateRange` hands to git, RFC 2822 dates, Unix timestamps and
+/// relative dates such as "3 days ago".
+fn parse_date(input: &str) -> Result<i64> {
+    if let Ok(dt) = NaiveDateTime::parse_from_str(input, "%Y-%m-%d %H:%M:%S") {
+        if let Some(local) = Local.from_local_datetime(&dt).earliest() {
+            return Ok(local.timestamp());
+        }
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        if let Some(local) = date
+            .and_hms_opt(0, 0, 0)
+            .and_then(|dt| Local.from_local_datetime(&dt).earliest())
+        {
+            return Ok(local.timestamp());
+        }
+    }
+
+    gix::date::parse(input, Some(std::time::SystemTime::now()))
+        .map(|time| time.seconds)
+        .with_context(|| format!("Unrecognized date: {}", input))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_date_forms() {
+        assert_eq!(
+            parse_date("2024-01-15 10:00:00 +0200").unwrap(),
+            1_705_305_600
+        );
+        assert_eq!(
+            parse_date("2024-01-15 08:00:00 +0000").unwrap(),
+            1_705_305_600
+        );
+        let local = Local
+            .from_local_datetime(
+                &NaiveDate::from_ymd_opt(2024, 1, 15)
+                    .unwrap()
+                    .and_hms_opt(0, 0, 0)
+                    .unwrap(),
+            )
+            .earliest()
+            .unwrap();
+        assert_eq!(parse_date("2024-01-15").unwrap(), local.timestamp());
+        assert_eq!(
+            parse_date("2024-01-15 00:00:00").unwrap(),
+            local.timestamp()
+        );
+        assert!(parse_date("3 days ago").is_ok());
+        assert!(parse_date("not a date").is_err());
+    }
+}
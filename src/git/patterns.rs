@@ -0,0 +1,125 @@
+use anyhow::Result;
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// Translate a gitignore-style pattern (as used by CODEOWNERS) into globs over repository-relative paths.
+///
+/// A leading or inner slash anchors the pattern to the root, otherwise it
+/// matches at any depth; a trailing slash only matches directories. A
+/// pattern naming a directory (a trailing slash, or no glob in the last
+/// segment) matches everything below it, while `dir/*` only matches direct
+/// children. `*` never crosses a `/`.
+pub fn compile_gitignore_pattern(pattern: &str) -> Result<GlobSet> {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_start_matches('/').trim_end_matches('/');
+
+    let anchored = pattern.starts_with('/') || trimmed.contains('/');
+    let base = if anchored {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+
+    let last_segment = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    let names_directory = dir_only || !last_segment.contains(['*', '?', '[']);
+
+    let mut globs = Vec::new();
+    if names_directory {
+        globs.push(format!("{}/**", base));
+    }
+    if !dir_only {
+        globs.push(base);
+    }
+
+    build(&globs)
+}
+
+/// Translate a .gitattributes pattern into a glob over repository-relative
+/// paths.
+///
+/// Anchoring works as for gitignore, but a pattern only matches paths
+/// themselves: naming a directory does not cover the files below it (that
+/// takes `dir/**`), and a pattern with a trailing slash matches nothing.
+pub fn compile_gitattributes_pattern(pattern: &str) -> Result<GlobSet> {
+    if pattern.ends_with('/') {
+        return build(&[]);
+    }
+    let trimmed = pattern.trim_start_matches('/');
+    let anchored = pattern.starts_with('/') || trimmed.contains('/');
+    let glob = if anchored {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+    build(&[glob])
+}
+
+fn build(globs: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(GlobBuilder::new(glob).literal_separator(true).build()?);
+    }
+    Ok(builder.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        compile_gitignore_pattern(pattern).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn unanchored_pattern_matches_at_any_depth() {
+        assert!(matches("*.md", "README.md"));
+        assert!(matches("*.md", "docs/guide/intro.md"));
+        assert!(!matches("*.md", "src/main.rs"));
+    }
+
+    #[test]
+    fn slash_anchors_to_root() {
+        assert!(matches("/build", "build/out.o"));
+        assert!(!matches("/build", "src/build/out.o"));
+        assert!(matches("src/lib", "src/lib/mod.rs"));
+        assert!(!matches("src/lib", "vendor/src/lib/mod.rs"));
+    }
+
+    #[test]
+    fn directory_matches_everything_below() {
+        assert!(matches("docs", "docs/a.md"));
+        assert!(matches("docs", "docs/sub/b.md"));
+        assert!(matches("docs/", "docs/sub/b.md"));
+        assert!(matches("docs/**", "docs/sub/b.md"));
+    }
+
+    #[test]
+    fn trailing_slash_only_matches_directories() {
+        assert!(!matches("docs/", "docs"));
+        assert!(matches("docs", "docs"));
+    }
+
+    #[test]
+    fn star_matches_direct_children_only() {
+        assert!(matches("docs/*", "docs/a.md"));
+        assert!(!matches("docs/*", "docs/sub/b.md"));
+        assert!(!matches("/*.md", "docs/a.md"));
+        assert!(matches("/*.md", "README.md"));
+    }
+
+    #[test]
+    fn attribute_patterns_do_not_cover_directories() {
+        let matches = |pattern: &str, path: &str| {
+            compile_gitattributes_pattern(pattern)
+                .unwrap()
+                .is_match(path)
+        };
+        assert!(!matches("vendor", "vendor/lib.js"));
+        assert!(matches("vendor", "src/vendor"));
+        assert!(matches("vendor/**", "vendor/a/lib.js"));
+        assert!(!matches("vendor/", "vendor/lib.js"));
+        assert!(matches("*.pb.go", "api/v1/service.pb.go"));
+        assert!(matches("/dist/*.js", "dist/app.js"));
+        assert!(!matches("/dist/*.js", "web/dist/app.js"));
+        assert!(!matches("dist/*.js", "dist/sub/app.js"));
+    }
+}
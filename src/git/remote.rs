@@ -0,0 +1,76 @@
+/// Browser URL of a repository given its remote URL, e.g.
+/// `git@github.com:owner/repo.git` becomes `https://github.com/owner/repo`.
+///
+/// Handles `https://`, `ssh://`, `git://` and scp-like remotes; local paths
+/// have no web URL.
+pub fn web_url(remote: &str) -> Option<String> {
+    let remote = remote.trim().trim_end_matches('/');
+    let remote = remote.strip_suffix(".git").unwrap_or(remote);
+
+    let mut web_scheme = "https";
+    let (host, path) = if let Some((scheme, rest)) = remote.split_once("://") {
+        if !matches!(scheme, "http" | "https" | "ssh" | "git" | "git+ssh") {
+            return None;
+        }
+        let (authority, path) = rest.split_once('/')?;
+        let host = authority.rsplit('@').next()?;
+        // Only ssh ports are dropped; an http port is part of the web address
+        let host = if scheme.starts_with("http") {
+            web_scheme = scheme;
+            host
+        } else {
+            host.split(':').next()?
+        };
+        (host, path)
+    } else {
+        // scp-like syntax: [user@]host:path
+        let (authority, path) = remote.split_once(':')?;
+        if authority.contains('/') || path.starts_with('/') {
+            return None;
+        }
+        (authority.rsplit('@').next()?, path)
+    };
+
+    if host.is_empty() || path.is_empty() {
+        return None;
+    }
+    Some(format!("{}://{}/{}", web_scheme, host, path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remotes_map_to_web_urls() {
+        let url = |remote| web_url(remote);
+        let github = Some("https://github.com/acme/app".to_string());
+        assert_eq!(url("git@github.com:acme/app.git"), github);
+        assert_eq!(url("github.com:acme/app"), github);
+        assert_eq!(url("ssh://git@github.com:2222/acme/app.git"), github);
+        assert_eq!(url("git+ssh://github.com/acme/app"), github);
+        assert_eq!(url("git://github.com/acme/app.git"), github);
+        assert_eq!(url("https://github.com/acme/app.git\n"), github);
+        assert_eq!(url("https://user@github.com/acme/app/"), github);
+        assert_eq!(
+            url("http://git.example.com:8080/team/app.git").as_deref(),
+            Some("http://git.example.com:8080/team/app")
+        );
+        assert_eq!(
+            url("git@gitlab.com:group/sub/app.git").as_deref(),
+            Some("https://gitlab.com/group/sub/app")
+        );
+    }
+
+    #[test]
+    fn local_remotes_have_no_web_url() {
+        for remote in [
+            "/srv/git/app.git",
+            "../app",
+            "file:///srv/git/app.git",
+            "C:/repos/app",
+        ] {
+            assert_eq!(web_url(remote), None, "{}", remote);
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use anyhow::{Context, Result};
+use std::io::{BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+use super::{conventional, trailers, Commit, FileChange, GitBackend, Person, RevRange};
+
+/// Each commit starts with a record separator followed by NUL-terminated
+/// header fields: hash, short hash, author name/email, committer name/email,
+/// author date, committer date, parents, subject and body. The commit's
+/// numstat entries follow the body; with `-z` they are NUL-terminated too.
+const LOG_FORMAT: &str =
+    "--format=%x1e%H%x00%h%x00%an%x00%ae%x00%cn%x00%ce%x00%aI%x00%cI%x00%P%x00%s%x00%b%x00";
+
+/// Number of NUL-terminated header fields in `LOG_FORMAT`
+const HEADER_FIELDS: usize = 11;
+
+const RECORD_SEPARATOR: u8 = 0x1e;
+
+/// Backend that shells out to the `git` binary and parses its text output
+pub struct SubprocessBackend {
+    path: String,
+}
+
+impl SubprocessBackend {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+
+    fn run_git(&self, args: &[&str]) -> Result<String> {
+        let output = Command::new("git")
+            .args(["-C", &self.path])
+            .args(args)
+            .output()
+            .context("Failed to execute git command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("Git command failed: {}", stderr);
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Spawn a git command and hand its stdout to `f` as a buffered stream,
+    /// so large outputs can be parsed without holding them in memory.
+    fn stream_git<T>(
+        &self,
+        args: &[&str],
+        f: impl FnOnce(&mut dyn BufRead) -> Result<T>,
+    ) -> Result<T> {
+        let mut child = Command::new("git")
+            .args(["-C", &self.path])
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .context("Failed to execute git command")?;
+
+        let stdout = child
+            .stdout
+            .take()
+            .context("Failed to capture git output")?;
+        // Read stderr alongside, or git blocks once the pipe fills up while
+        // we wait for more stdout
+        let mut stderr = child
+            .stderr
+            .take()
+            .context("Failed to capture git output")?;
+        let stderr = std::thread::spawn(move || {
+            let mut output = Vec::new();
+            stderr.read_to_end(&mut output).map(|_| output)
+        });
+
+        // Dropping the reader closes stdout, so git stops if `f` returns early
+        let result = f(&mut BufReader::new(stdout));
+
+        let status = child.wait().context("Failed to wait for git command")?;
+        let stderr = stderr
+            .join()
+            .ok()
+            .and_then(|output| output.ok())
+            .unwrap_or_default();
+        let stderr = String::from_utf8_lossy(&stderr);
+        // When `f` stopped reading, git's exit is our doing
+        if !status.success() && (result.is_ok() || !stderr.trim().is_empty()) {
+            anyhow::bail!("Git command failed: {}", stderr);
+        }
+
+        result
+    }
+}
+
+impl GitBackend for SubprocessBackend {
+    fn current_branch(&self) -> Result<String> {
+        let output = self.run_git(&["rev-parse", "--abbrev-ref", "HEAD"])?;
+        Ok(output.trim().to_string())
+    }
+
+    fn workdir(&self) -> Result<Option<PathBuf>> {
+        // Fails for bare repositories, which have no working tree
+        let toplevel = self.run_git(&["rev-parse", "--show-toplevel"]).ok();
+        Ok(toplevel
+            .map(|path| PathBuf::from(path.trim()))
+            .filter(|path| !path.as_os_str().is_empty()))
+    }
+
+    fn config_value(&self, key: &str) -> Result<Option<String>> {
+        // `git config --get` exits with 1 when the key is unset
+        let output = Command::new("git")
+            .args(["-C", &self.path, "config", "--get", key])
+            .output()
+            .context("Failed to execute git command")?;
+
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(value).filter(|v| !v.is_empty()))
+    }
+
+    fn commits(
+        &self,
+        range: &RevRange,
+        since: Option<&str>,
+        until: Option<&str>,
+    ) -> Result<Vec<Commit>> {
+        let revisions = range.to_string();
+        let mut args = vec!["log", revisions.as_str(), "--numstat", "-z", LOG_FORMAT];
+
+        let since_arg = since.map(|s| format!("--since={}", s));
+        if let Some(ref s) = since_arg {
+            args.push(s);
+        }
+
+        let until_arg = until.map(|u| format!("--until={}", u));
+        if let Some(ref u) = until_arg {
+            args.push(u);
+        }
+
+        self.stream_git(&args, parse_log)
+    }
+
+    fn latest_tag(&self, rev: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .args(["-C", &self.path, "describe", "--tags", "--abbrev=0", rev])
+            .output()
+            .context("Failed to execute git command")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            // Exit code 128 with these messages means there is simply no tag
+            if stderr.contains("No names found") || stderr.contains("No tags can describe") {
+                return Ok(None);
+            }
+            anyhow::bail!("Git command failed: {}", stderr);
+        }
+
+        let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok(Some(tag).filter(|t| !t.is_empty()))
+    }
+
+    fn tags(&self, rev: &str) -> Result<Vec<String>> {
+        let output = self.run_git(&["tag", "--merged", rev])?;
+        Ok(output.lines().map(str::to_string).collect())
+    }
+}
+
+/// Parse `git log --numstat` output produced with `LOG_FORMAT`.
+///
+/// Records are split on the record separator (0x1e). A separator that shows
+/// up inside a commit message leaves the header short of fields, in which
+/// case it is kept as text and the record continues.
+fn parse_log(reader: &mut dyn BufRead) -> Result<Vec<Commit>> {
+    let mut commits = Vec::new();
+    let mut record = Vec::new();
+    let mut chunk = Vec::new();
+
+    loop {
+        chunk.clear();
+        if reader
+            .read_until(RECORD_SEPARATOR, &mut chunk)
+            .context("Failed to read git output")?
+            == 0
+        {
+            break;
+        }
+
+        let at_separator = chunk.last() == Some(&RECORD_SEPARATOR);
+        if at_separator {
+            chunk.pop();
+        }
+        record.extend_from_slice(&chunk);
+
+        if !at_separator {
+            continue;
+        }
+
+        let fields = record.iter().filter(|&&b| b == 0).count();
+        if record.is_empty() || fields >= HEADER_FIELDS {
+            if let Some(commit) = parse_record(&record) {
+                commits.push(commit);
+            }
+            record.clear();
+        } else {
+            record.push(RECORD_SEPARATOR);
+        }
+    }
+
+    if let Some(commit) = parse_record(&record) {
+        commits.push(commit);
+    }
+
+    Ok(commits)
+}
+
+/// Parse one commit record: NUL-terminated header fields followed by numstat entries
+fn parse_record(record: &[u8]) -> Option<Commit> {
+    let record = String::from_utf8_lossy(record);
+    let parts: Vec<&str> = record.splitn(HEADER_FIELDS + 1, '\0').collect();
+    if parts.len() <= HEADER_FIELDS {
+        return None;
+    }
+
+    let files_changed = parse_numstat(parts[HEADER_FIELDS]);
+
+    let body = parts[10].trim().to_string();
+    let trailers = trailers::parse(&body);
+
+    Some(Commit {
+        hash: parts[0].to_string(),
+        short_hash: parts[1].to_string(),
+        author: Person {
+            name: parts[2].to_string(),
+            email: parts[3].to_string(),
+        },
+        committer: Person {
+            name: parts[4].to_string(),
+            email: parts[5].to_string(),
+        },
+        timestamp: parts[6].to_string(),
+        commit_timestamp: parts[7].to_string(),
+        parents: parts[8].split_whitespace().map(String::from).collect(),
+        message: parts[9].to_string(),
+        conventional: conventional::parse(parts[9], &trailers),
+        body,
+        co_authors: trailers::co_authors(&trailers),
+        trailers,
+        files_changed,
+    })
+}
+
+/// Parse `--numstat -z` entries: "additions<TAB>deletions<TAB>path" each
+/// followed by a NUL. Renames and copies leave the path empty and follow it
+/// with the old and new paths; the new one is reported, as `NativeBackend`
+/// does.
+fn parse_numstat(text: &str) -> Vec<FileChange> {
+    let mut fields = text
+        .split('\0')
+        .map(|field| field.trim_start_matches('\n'))
+        .filter(|field| !field.is_empty());
+
+    let mut changes = Vec::new();
+    while let Some(field) = fields.next() {
+        let parts: Vec<&str> = field.splitn(3, '\t').collect();
+        if parts.len() < 3 {
+            continue;
+        }
+        let path = if parts[2].is_empty() {
+            fields.next();
+            match fields.next() {
+                Some(path) => path,
+                None => break,
+            }
+        } else {
+            parts[2]
+        };
+
+        // Handle binary files (shown as "-")
+        changes.push(FileChange {
+            additions: parts[0].parse().unwrap_or(0),
+            deletions: parts[1].parse().unwrap_or(0),
+            path: path.to_string(),
+        });
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One commit as `git log --numstat -z` prints it with `LOG_FORMAT`;
+    /// `numstat` holds the NUL-terminated entries
+    fn record(hash: &str, parents: &str, subject: &str, body: &str, numstat: &str) -> String {
+        let date = "2024-01-15T10:00:00+01:00";
+        // A newline separates the message from the entries, if there are any
+        let separator = if numstat.is_empty() { "" } else { "\n" };
+        format!(
+            "\x1e{hash}\0{short}\0Alice\0alice@example.com\0Bob\0bob@example.com\0\
+             {date}\0{date}\0{parents}\0{subject}\0{body}\0\0{separator}{numstat}",
+            short = &hash[..7],
+        )
+    }
+
+    fn parse(output: &str) -> Vec<Commit> {
+        parse_log(&mut output.as_bytes()).unwrap()
+    }
+
+    fn files(commit: &Commit) -> Vec<(&str, i32, i32)> {
+        commit
+            .files_changed
+            .iter()
+            .map(|f| (f.path.as_str(), f.additions, f.deletions))
+            .collect()
+    }
+
+    #[test]
+    fn subject_with_pipes() {
+        let output = record(
+            "1111111aaaa",
+            "0000000ffff",
+            "fix: a | b || c",
+            "",
+            "1\t1\tsrc/a|b.rs\0",
+        );
+
+        let commits = parse(&output);
+
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].message, "fix: a | b || c");
+        assert_eq!(commits[0].author.name, "Alice");
+        assert_eq!(commits[0].committer.email, "bob@example.com");
+        assert_eq!(commits[0].short_hash, "1111111");
+        assert_eq!(files(&commits[0]), [("src/a|b.rs", 1, 1)]);
+    }
+
+    #[test]
+    fn body_with_paragraphs_and_trailers() {
+        let body = "First paragraph\nwraps here.\n\nSecond paragraph.\n\n\
+                    Co-authored-by: Carol <carol@example.com>\n\
+                    BREAKING CHANGE: the config moved";
+        let output = record("1111111aaaa", "0000000ffff", "feat: config", body, "")
+            + &record(
+                "2222222bbbb",
+                "1111111aaaa",
+                "fix: next",
+                "",
+                "1\t0\tb.rs\0",
+            );
+
+        let commits = parse(&output);
+
+        assert_eq!(commits.len(), 2);
+        assert_eq!(commits[0].body, body);
+        assert_eq!(commits[0].co_authors[0].name, "Carol");
+        assert!(commits[0].conventional.breaking);
+        assert_eq!(
+            commits[0].conventional.breaking_change.as_deref(),
+            Some("the config moved")
+        );
+        assert_eq!(commits[1].message, "fix: next");
+    }
+
+    #[test]
+    fn record_separator_inside_a_message() {
+        let output = record(
+            "1111111aaaa",
+            "",
+            "chore: odd",
+            "before\x1eafter",
+            "1\t0\ta.rs\0",
+        );
+
+        let commits = parse(&output);
+
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].body, "before\x1eafter");
+        assert_eq!(files(&commits[0]), [("a.rs", 1, 0)]);
+    }
+
+    #[test]
+    fn binary_files_count_no_lines() {
+        let output = record(
+            "1111111aaaa",
+            "0000000ffff",
+            "docs: add logo",
+            "",
+            "-\t-\tdocs/logo.png\0",
+        );
+
+        assert_eq!(files(&parse(&output)[0]), [("docs/logo.png", 0, 0)]);
+    }
+
+    #[test]
+    fn merge_with_several_parents() {
+        let output = record(
+            "3333333cccc",
+            "1111111aaaa 2222222bbbb 4444444dddd",
+            "Merge branches 'a' and 'b'",
+            "",
+            "",
+        );
+
+        let commits = parse(&output);
+
+        assert_eq!(
+            commits[0].parents,
+            ["1111111aaaa", "2222222bbbb", "4444444dddd"]
+        );
+        assert!(commits[0].files_changed.is_empty());
+    }
+
+    #[test]
+    fn commit_without_files() {
+        let output = record("1111111aaaa", "", "chore: empty", "", "")
+            + &record("2222222bbbb", "", "chore: root", "", "");
+
+        let commits = parse(&output);
+
+        assert_eq!(commits.len(), 2);
+        assert!(commits.iter().all(|c| c.files_changed.is_empty()));
+        assert!(commits[1].parents.is_empty());
+    }
+
+    #[test]
+    fn renames_report_the_new_path() {
+        let output = record(
+            "1111111aaaa",
+            "0000000ffff",
+            "refactor: move modules",
+            "",
+            "0\t0\t\0src/a.rs\0lib/a.rs\0\
+             3\t1\t\0src/b.rs\0src/c.rs\0\
+             2\t0\tREADME.md\0",
+        );
+
+        let commits = parse(&output);
+
+        assert_eq!(
+            files(&commits[0]),
+            [("lib/a.rs", 0, 0), ("src/c.rs", 3, 1), ("README.md", 2, 0)]
+        );
+    }
+}
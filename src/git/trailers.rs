@@ -0,0 +1,71 @@
+use serde::Serialize;
+
+use super::Person;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
+}
+
+/// Parse the trailer block ("Key: value" lines) from a commit body.
+///
+/// Like `git interpret-trailers`, only the final paragraph is considered and
+/// every line in it must be a trailer or an indented continuation.
+pub fn parse(body: &str) -> Vec<Trailer> {
+    let Some(paragraph) = body.trim_end().rsplit("\n\n").next() else {
+        return Vec::new();
+    };
+
+    let mut trailers: Vec<Trailer> = Vec::new();
+    for line in paragraph.lines() {
+        if line.starts_with([' ', '\t']) {
+            match trailers.last_mut() {
+                Some(last) => {
+                    last.value.push(' ');
+                    last.value.push_str(line.trim());
+                    continue;
+                }
+                None => return Vec::new(),
+            }
+        }
+
+        let Some((key, value)) = line.split_once(':') else {
+            return Vec::new();
+        };
+        // Conventional Commits allows a space in this one footer key
+        let key = key.trim_end();
+        let valid = key == "BREAKING CHANGE"
+            || (!key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+        if !valid {
+            return Vec::new();
+        }
+
+        trailers.push(Trailer {
+            key: key.to_string(),
+            value: value.trim().to_string(),
+        });
+    }
+
+    trailers
+}
+
+/// People credited through `Co-authored-by:` trailers
+pub fn co_authors(trailers: &[Trailer]) -> Vec<Person> {
+    trailers
+        .iter()
+        .filter(|t| t.key.eq_ignore_ascii_case("co-authored-by"))
+        .filter_map(|t| parse_person(&t.value))
+        .collect()
+}
+
+/// Parse "Name <email>" into a `Person`
+fn parse_person(value: &str) -> Option<Person> {
+    let open = value.find('<')?;
+    let close = open + value[open..].find('>')?;
+
+    Some(Person {
+        name: value[..open].trim().to_string(),
+        email: value[open + 1..close].trim().to_string(),
+    })
+}
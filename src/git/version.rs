@@ -0,0 +1,165 @@
+use semver::Version;
+use serde::Serialize;
+
+use super::Commit;
+
+/// Which part of the version a release increments
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl Bump {
+    /// Major for breaking changes, minor for features, patch for anything
+    /// else (fixes included)
+    pub fn for_commits(commits: &[Commit]) -> Bump {
+        commits
+            .iter()
+            .filter(|c| c.parents.len() <= 1)
+            .map(|c| {
+                if c.conventional.breaking {
+                    Bump::Major
+                } else if c.conventional.kind.as_deref() == Some("feat") {
+                    Bump::Minor
+                } else {
+                    Bump::Patch
+                }
+            })
+            .max()
+            .unwrap_or(Bump::Patch)
+    }
+
+    pub fn apply(&self, version: &Version) -> Version {
+        match self {
+            Bump::Major => Version::new(version.major + 1, 0, 0),
+            Bump::Minor => Version::new(version.major, version.minor + 1, 0),
+            // Releasing a pre-release means dropping its suffix
+            Bump::Patch if !version.pre.is_empty() => {
+                Version::new(version.major, version.minor, version.patch)
+            }
+            Bump::Patch => Version::new(version.major, version.minor, version.patch + 1),
+        }
+    }
+}
+
+impl std::fmt::Display for Bump {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Bump::Major => "major",
+            Bump::Minor => "minor",
+            Bump::Patch => "patch",
+        })
+    }
+}
+
+/// Suggested next release, from the commits since the latest version tag
+#[derive(Debug, Clone, Serialize)]
+pub struct NextVersion {
+    /// Latest version tag reachable from the branch; `None` before the first
+    /// release, which is treated as 0.0.0
+    pub current: Option<String>,
+    /// The next version as a tag, keeping the current tag's prefix
+    pub next: String,
+    pub bump: Bump,
+    /// Commits since `current`
+    pub commit_count: usize,
+    pub breaking_count: usize,
+}
+
+impl NextVersion {
+    pub fn new(current: Option<(&str, &Version)>, commits: &[Commit]) -> Self {
+        let (tag, prefix, version) = match current {
+            Some((tag, version)) => (Some(tag.to_string()), tag_prefix(tag), version.clone()),
+            None => (None, "v", Version::new(0, 0, 0)),
+        };
+        let bump = Bump::for_commits(commits);
+
+        Self {
+            current: tag,
+            next: format!("{}{}", prefix, bump.apply(&version)),
+            bump,
+            commit_count: commits.len(),
+            breaking_count: commits.iter().filter(|c| c.conventional.breaking).count(),
+        }
+    }
+}
+
+/// Version named by a tag such as "v1.2.3", "1.2.3" or "release-1.2.3"
+pub fn parse_tag(tag: &str) -> Option<Version> {
+    Version::parse(&tag[tag_prefix(tag).len()..]).ok()
+}
+
+/// Everything before the first digit
+fn tag_prefix(tag: &str) -> &str {
+    let start = tag.find(|c: char| c.is_ascii_digit()).unwrap_or(tag.len());
+    &tag[..start]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::fake::commit;
+
+    fn v(version: &str) -> Version {
+        Version::parse(version).unwrap()
+    }
+
+    #[test]
+    fn apply() {
+        assert_eq!(Bump::Major.apply(&v("1.2.3")), v("2.0.0"));
+        assert_eq!(Bump::Minor.apply(&v("1.2.3")), v("1.3.0"));
+        assert_eq!(Bump::Patch.apply(&v("1.2.3")), v("1.2.4"));
+        assert_eq!(Bump::Patch.apply(&v("1.3.0-rc.1")), v("1.3.0"));
+        assert_eq!(Bump::Minor.apply(&v("1.3.0-rc.1")), v("1.4.0"));
+        assert_eq!(Bump::Major.apply(&v("0.9.1+build.5")), v("1.0.0"));
+    }
+
+    #[test]
+    fn bump_for_commits() {
+        let fix = commit("c1", "fix: crash on start");
+        let feat = commit("c2", "feat: add export");
+        let breaking = commit("c3", "docs: new config\n\nBREAKING CHANGE: TOML only");
+        let mut merge = commit("c4", "feat!: merged from a branch");
+        merge.parents = vec!["a".to_string(), "b".to_string()];
+
+        assert_eq!(Bump::for_commits(&[]), Bump::Patch);
+        assert_eq!(Bump::for_commits(std::slice::from_ref(&fix)), Bump::Patch);
+        assert_eq!(Bump::for_commits(&[fix.clone(), feat.clone()]), Bump::Minor);
+        assert_eq!(
+            Bump::for_commits(&[feat, breaking, fix.clone()]),
+            Bump::Major
+        );
+        // Merge commits repeat what they merge
+        assert_eq!(Bump::for_commits(&[fix, merge]), Bump::Patch);
+    }
+
+    #[test]
+    fn next_version_keeps_the_tag_prefix() {
+        let commits = [commit("c1", "feat: add export")];
+
+        let next = NextVersion::new(Some(("api-v1.2.3", &v("1.2.3"))), &commits);
+        assert_eq!(next.current.as_deref(), Some("api-v1.2.3"));
+        assert_eq!(next.next, "api-v1.3.0");
+        assert_eq!((next.bump, next.commit_count), (Bump::Minor, 1));
+
+        assert_eq!(
+            NextVersion::new(Some(("1.2.3", &v("1.2.3"))), &commits).next,
+            "1.3.0"
+        );
+
+        let first = NextVersion::new(None, &commits);
+        assert_eq!((first.current, first.next.as_str()), (None, "v0.1.0"));
+    }
+
+    #[test]
+    fn parse_tags() {
+        assert_eq!(parse_tag("v1.2.3"), Some(v("1.2.3")));
+        assert_eq!(parse_tag("1.2.3-beta.1"), Some(v("1.2.3-beta.1")));
+        assert_eq!(parse_tag("release-1.2.3"), Some(v("1.2.3")));
+        assert_eq!(parse_tag("v1.2"), None);
+        assert_eq!(parse_tag("latest"), None);
+    }
+}
@@ -0,0 +1,422 @@
+use anyhow::{Context, Result};
+use globset::{GlobBuilder, GlobMatcher};
+use std::path::{Path, PathBuf};
+
+/// A workspace member: Cargo crate, npm/pnpm package or Go module
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub name: String,
+    /// Directory relative to the repository root, without a trailing slash
+    pub path: String,
+}
+
+/// Detect workspace members declared in the root `Cargo.toml`,
+/// `package.json`/`pnpm-workspace.yaml` and `go.work`
+pub fn detect_packages(root: &Path) -> Result<Vec<Package>> {
+    let mut packages = Vec::new();
+    packages.extend(cargo_members(root)?);
+    packages.extend(npm_members(root)?);
+    packages.extend(go_members(root)?);
+
+    // A directory listed by several workspace tools is still one package
+    packages.sort_by(|a, b| a.path.cmp(&b.path));
+    packages.dedup_by(|a, b| a.path == b.path);
+    Ok(packages)
+}
+
+fn cargo_members(root: &Path) -> Result<Vec<Package>> {
+    let Some(manifest) = read_toml(&root.join("Cargo.toml"))? else {
+        return Ok(Vec::new());
+    };
+    let Some(workspace) = manifest.get("workspace") else {
+        return Ok(Vec::new());
+    };
+
+    let members = string_array(workspace.get("members"));
+    let excludes = string_array(workspace.get("exclude"));
+
+    let mut packages = Vec::new();
+    for dir in expand_members(root, &members, &excludes)? {
+        let Some(crate_manifest) = read_toml(&root.join(&dir).join("Cargo.toml"))? else {
+            continue;
+        };
+        let name = crate_manifest
+            .get("package")
+            .and_then(|p| p.get("name"))
+            .and_then(|n| n.as_str())
+            .map(String::from);
+        packages.push(package(dir, name));
+    }
+    Ok(packages)
+}
+
+fn npm_members(root: &Path) -> Result<Vec<Package>> {
+    let mut patterns = Vec::new();
+
+    if let Some(manifest) = read_json(&root.join("package.json"))? {
+        // "workspaces": [...] or "workspaces": { "packages": [...] }
+        let workspaces = manifest.get("workspaces");
+        let list = workspaces
+            .and_then(|w| w.get("packages"))
+            .or(workspaces)
+            .and_then(|w| w.as_array());
+        patterns.extend(
+            list.into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().map(String::from)),
+        );
+    }
+
+    let pnpm = root.join("pnpm-workspace.yaml");
+    if pnpm.is_file() {
+        let text = std::fs::read_to_string(&pnpm)
+            .with_context(|| format!("Failed to read {}", pnpm.display()))?;
+        patterns.extend(pnpm_packages(&text));
+    }
+
+    let (excludes, members): (Vec<String>, Vec<String>) =
+        patterns.into_iter().partition(|p| p.starts_with('!'));
+    let excludes: Vec<String> = excludes.iter().map(|p| p[1..].to_string()).collect();
+
+    let mut packages = Vec::new();
+    for dir in expand_members(root, &members, &excludes)? {
+        let Some(manifest) = read_json(&root.join(&dir).join("package.json"))? else {
+            continue;
+        };
+        let name = manifest
+            .get("name")
+            .and_then(|n| n.as_str())
+            .map(String::from);
+        packages.push(package(dir, name));
+    }
+    Ok(packages)
+}
+
+/// Read the `packages:` list from `pnpm-workspace.yaml`. Only the simple
+/// block-list form is supported, which is what pnpm documents.
+fn pnpm_packages(text: &str) -> Vec<String> {
+    let mut patterns = Vec::new();
+    let mut in_packages = false;
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            patterns.push(item.trim().trim_matches(['\'', '"']).to_string());
+        }
+    }
+    patterns
+}
+
+fn go_members(root: &Path) -> Result<Vec<Package>> {
+    let go_work = root.join("go.work");
+    if !go_work.is_file() {
+        return Ok(Vec::new());
+    }
+    let text = std::fs::read_to_string(&go_work)
+        .with_context(|| format!("Failed to read {}", go_work.display()))?;
+
+    // `use ./dir` or a `use ( ... )` block
+    let mut dirs = Vec::new();
+    let mut in_block = false;
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                dirs.push(line.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                dirs.push(rest.to_string());
+            }
+        }
+    }
+
+    let mut packages = Vec::new();
+    for dir in dirs {
+        let dir = normalize(dir.trim_matches('"'));
+        // The root module is not a package of its own
+        if dir.is_empty() || dir == "." {
+            continue;
+        }
+        let go_mod = root.join(&dir).join("go.mod");
+        if !go_mod.is_file() {
+            continue;
+        }
+        let text = std::fs::read_to_string(&go_mod)
+            .with_context(|| format!("Failed to read {}", go_mod.display()))?;
+        let name = text
+            .lines()
+            .find_map(|l| l.trim().strip_prefix("module "))
+            .map(|m| m.trim().to_string());
+        packages.push(package(dir, name));
+    }
+    Ok(packages)
+}
+
+fn package(dir: String, name: Option<String>) -> Package {
+    Package {
+        name: name.unwrap_or_else(|| format!("{}/", dir)),
+        path: dir,
+    }
+}
+
+/// Expand member globs such as `crates/*` into directories relative to
+/// `root`, dropping any that match an exclude pattern
+fn expand_members(root: &Path, members: &[String], excludes: &[String]) -> Result<Vec<String>> {
+    let excludes = excludes
+        .iter()
+        .map(|p| compile(&normalize(p)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut dirs = Vec::new();
+    for member in members {
+        let pattern = normalize(member);
+        if !pattern.contains(['*', '?', '[', '{']) {
+            dirs.push(pattern);
+            continue;
+        }
+
+        let matcher = compile(&pattern)?;
+        // Only walk as deep as the pattern could reach
+        let depth = if pattern.contains("**") {
+            usize::MAX
+        } else {
+            pattern.split('/').count()
+        };
+        for dir in list_dirs(root, PathBuf::new(), depth) {
+            let dir = dir.to_string_lossy().replace('\\', "/");
+            if matcher.is_match(&dir) {
+                dirs.push(dir);
+            }
+        }
+    }
+
+    dirs.retain(|d| !d.is_empty() && d != "." && !excludes.iter().any(|e| e.is_match(d)));
+    Ok(dirs)
+}
+
+/// Recursively list directories below `root/relative`, skipping hidden
+/// directories and dependency folders
+fn list_dirs(root: &Path, relative: PathBuf, depth: usize) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if depth == 0 {
+        return dirs;
+    }
+    let Ok(entries) = std::fs::read_dir(root.join(&relative)) else {
+        return dirs;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || name == "node_modules" || name == "target" {
+            continue;
+        }
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            let child = relative.join(name.as_ref());
+            dirs.extend(list_dirs(root, child.clone(), depth - 1));
+            dirs.push(child);
+        }
+    }
+    dirs
+}
+
+fn compile(pattern: &str) -> Result<GlobMatcher> {
+    Ok(GlobBuilder::new(pattern)
+        .literal_separator(true)
+        .build()
+        .with_context(|| format!("Invalid workspace member pattern: {}", pattern))?
+        .compile_matcher())
+}
+
+/// Strip `./` prefixes and trailing slashes from a member path
+fn normalize(path: &str) -> String {
+    path.trim()
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .to_string()
+}
+
+fn read_toml(path: &Path) -> Result<Option<toml::Value>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let value = toml::from_str(&text).with_context(|| format!("Invalid {}", path.display()))?;
+    Ok(Some(value))
+}
+
+fn read_json(path: &Path) -> Result<Option<serde_json::Value>> {
+    if !path.is_file() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    let value =
+        serde_json::from_str(&text).with_context(|| format!("Invalid {}", path.display()))?;
+    Ok(Some(value))
+}
+
+fn string_array(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Path, path: &str, text: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+
+    fn detect(root: &Path) -> Vec<(String, String)> {
+        detect_packages(root)
+            .unwrap()
+            .into_iter()
+            .map(|p| (p.path, p.name))
+            .collect()
+    }
+
+    fn pair(path: &str, name: &str) -> (String, String) {
+        (path.to_string(), name.to_string())
+    }
+
+    #[test]
+    fn cargo_workspace_members() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\", \"./tools/cli/\"]\nexclude = [\"crates/experimental\"]\n",
+        );
+        write(
+            root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"core\"\n",
+        );
+        write(
+            root,
+            "crates/experimental/Cargo.toml",
+            "[package]\nname = \"exp\"\n",
+        );
+        write(root, "crates/docs/README.md", "not a crate");
+        write(root, "tools/cli/Cargo.toml", "[package]\nname = \"cli\"\n");
+
+        assert_eq!(
+            detect(root),
+            [pair("crates/core", "core"), pair("tools/cli", "cli")]
+        );
+    }
+
+    #[test]
+    fn npm_and_pnpm_workspaces() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "package.json",
+            r#"{"workspaces": {"packages": ["packages/*", "!packages/legacy"]}}"#,
+        );
+        write(root, "packages/ui/package.json", r#"{"name": "@app/ui"}"#);
+        write(
+            root,
+            "packages/legacy/package.json",
+            r#"{"name": "legacy"}"#,
+        );
+        write(
+            root,
+            "pnpm-workspace.yaml",
+            "packages:\n  - 'apps/*'\n  # not yet\ncatalog:\n  - ignored\n",
+        );
+        write(root, "apps/web/package.json", r#"{"name": "web"}"#);
+        write(root, "apps/nameless/package.json", "{}");
+        write(root, "ignored/package.json", r#"{"name": "ignored"}"#);
+
+        assert_eq!(
+            detect(root),
+            [
+                pair("apps/nameless", "apps/nameless/"),
+                pair("apps/web", "web"),
+                pair("packages/ui", "@app/ui"),
+            ]
+        );
+    }
+
+    #[test]
+    fn go_work_modules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        write(
+            root,
+            "go.work",
+            "go 1.22\n\nuse (\n\t./svc/api // the API\n\t.\n)\nuse ./tools\n",
+        );
+        write(root, "go.mod", "module example.com/root\n");
+        write(
+            root,
+            "svc/api/go.mod",
+            "module example.com/api\n\ngo 1.22\n",
+        );
+        write(root, "tools/go.mod", "module example.com/tools\n");
+
+        assert_eq!(
+            detect(root),
+            [
+                pair("svc/api", "example.com/api"),
+                pair("tools", "example.com/tools"),
+            ]
+        );
+    }
+
+    #[test]
+    fn member_globs_expand_to_directories() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for path in [
+            "libs/a/nested/deep",
+            "libs/a/internal",
+            "libs/b/node_modules/dep",
+            "libs/.cache",
+            "apps/web/src",
+        ] {
+            std::fs::create_dir_all(root.join(path)).unwrap();
+        }
+        let strings = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        let expand = |members: &[&str], excludes: &[&str]| {
+            let mut dirs = expand_members(root, &strings(members), &strings(excludes)).unwrap();
+            dirs.sort();
+            dirs
+        };
+
+        assert_eq!(expand(&["libs/*"], &[]), ["libs/a", "libs/b"]);
+        assert_eq!(
+            expand(&["libs/**"], &["libs/a/internal"]),
+            ["libs/a", "libs/a/nested", "libs/a/nested/deep", "libs/b"]
+        );
+        assert_eq!(
+            expand(&["./apps/web/", "apps/*/src", "."], &[]),
+            ["apps/web", "apps/web/src"]
+        );
+    }
+}
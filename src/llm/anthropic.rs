@@ -0,0 +1,270 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::stream::Events;
+use super::{BoxFuture, HttpClient, LlmProvider, ProviderSettings};
+
+pub const DEFAULT_MODEL: &str = "claude-sonnet-4-20250514";
+
+const DEFAULT_BASE_URL: &str = "https://api.anthropic.com";
+
+#[derive(Debug, Serialize)]
+struct AnthropicRequest<'a> {
+    model: &'a str,
+    max_tokens: u32,
+    messages: Vec<Message<'a>>,
+    stream: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct AnthropicResponse {
+    content: Vec<ContentBlock>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContentBlock {
+    text: String,
+}
+
+/// The data of a server-sent event when streaming
+#[derive(Debug, Deserialize)]
+struct StreamEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    /// Set on `content_block_delta`
+    delta: Option<Delta>,
+    /// Set on `error`, e.g. when the API becomes overloaded mid-stream
+    error: Option<StreamError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Delta {
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamError {
+    message: String,
+}
+
+/// Claude via the Anthropic Messages API
+pub struct AnthropicProvider {
+    api_key: String,
+    base_url: String,
+    model: String,
+    max_output_tokens: u32,
+    client: HttpClient,
+}
+
+impl AnthropicProvider {
+    /// Read the API key from `ANTHROPIC_API_KEY` and, unless configured, the
+    /// endpoint from `ANTHROPIC_BASE_URL`
+    pub fn from_env(settings: &ProviderSettings) -> Result<Self> {
+        let api_key = std::env::var("ANTHROPIC_API_KEY").context("ANTHROPIC_API_KEY not set")?;
+        Self::new(api_key, settings)
+    }
+
+    /// Use `api_key` and, unless configured, the endpoint from
+    /// `ANTHROPIC_BASE_URL`
+    pub fn new(api_key: String, settings: &ProviderSettings) -> Result<Self> {
+        Ok(Self {
+            api_key,
+            base_url: settings.base_url("ANTHROPIC_BASE_URL", DEFAULT_BASE_URL),
+            model: settings
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            max_output_tokens: settings.max_output_tokens,
+            client: settings.client()?,
+        })
+    }
+
+    fn request(&self, prompt: &str, stream: bool) -> reqwest::RequestBuilder {
+        let request = AnthropicRequest {
+            model: &self.model,
+            max_tokens: self.max_output_tokens,
+            messages: vec![Message {
+                role: "user",
+                content: prompt,
+            }],
+            stream,
+        };
+
+        self.client
+            .post(format!("{}/v1/messages", self.base_url), stream)
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", "2023-06-01")
+            .json(&request)
+    }
+}
+
+impl LlmProvider for AnthropicProvider {
+    fn name(&self) -> &str {
+        "Anthropic"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn max_output_tokens(&self) -> u32 {
+        self.max_output_tokens
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let response: AnthropicResponse = self
+                .client
+                .send_json(self.request(prompt, false), self.name())
+                .await?;
+
+            response
+                .content
+                .into_iter()
+                .next()
+                .map(|c| c.text)
+                .context("No content in response")
+        })
+    }
+
+    fn complete_stream<'a>(
+        &'a self,
+        prompt: &'a str,
+        on_text: &'a mut (dyn FnMut(&str) + Send),
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .send(self.request(prompt, true), self.name())
+                .await?;
+
+            let mut events = Events::new(response, self.name());
+            let mut text = String::new();
+            while let Some(data) = events.next_data().await? {
+                let event: StreamEvent = serde_json::from_str(&data)
+                    .with_context(|| format!("Failed to parse {} stream event", self.name()))?;
+                match event.kind.as_str() {
+                    "content_block_delta" => {
+                        if let Some(delta) = event.delta.and_then(|d| d.text) {
+                            on_text(&delta);
+                            text.push_str(&delta);
+                        }
+                    }
+                    "error" => anyhow::bail!(
+                        "{} API error while streaming: {}",
+                        self.name(),
+                        event.error.map_or(data, |e| e.message)
+                    ),
+                    "message_stop" => break,
+                    _ => {}
+                }
+            }
+            Ok(text)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::test_server::{Reply, TestServer};
+    use serde_json::json;
+
+    fn provider(server: &TestServer) -> AnthropicProvider {
+        AnthropicProvider::new("test-key".to_string(), &server.settings("claude-test")).unwrap()
+    }
+
+    /// A server-sent event as the Messages API streams it
+    fn event(data: serde_json::Value) -> Vec<u8> {
+        format!(
+            "event: {}\ndata: {}\n\n",
+            data["type"].as_str().unwrap(),
+            data
+        )
+        .into_bytes()
+    }
+
+    fn delta(text: &str) -> Vec<u8> {
+        event(json!({
+            "type": "content_block_delta",
+            "index": 0,
+            "delta": {"type": "text_delta", "text": text}
+        }))
+    }
+
+    async fn stream(events: &[Vec<u8>]) -> (TestServer, Vec<String>, Result<String>) {
+        let chunks: Vec<&[u8]> = events.iter().map(Vec::as_slice).collect();
+        let server = TestServer::start(vec![Reply::stream("text/event-stream", &chunks)]).await;
+        let mut pieces = Vec::new();
+        let result = provider(&server)
+            .complete_stream("Prompt", &mut |text| pieces.push(text.to_string()))
+            .await;
+        (server, pieces, result)
+    }
+
+    #[tokio::test]
+    async fn complete_posts_message() {
+        let server = TestServer::start(vec![Reply::json(json!({
+            "content": [{"type": "text", "text": "Summary"}]
+        }))])
+        .await;
+
+        assert_eq!(
+            provider(&server).complete("Prompt").await.unwrap(),
+            "Summary"
+        );
+
+        let request = &server.requests()[0];
+        assert_eq!(request.path, "/v1/messages");
+        assert_eq!(request.headers["x-api-key"], "test-key");
+        assert_eq!(request.headers["anthropic-version"], "2023-06-01");
+        assert_eq!(request.body["model"], "claude-test");
+        assert_eq!(request.body["max_tokens"], 256);
+        assert_eq!(request.body["stream"], false);
+    }
+
+    #[tokio::test]
+    async fn stream_collects_deltas_until_message_stop() {
+        let (server, pieces, result) = stream(&[
+            event(json!({"type": "message_start", "message": {"id": "msg_1"}})),
+            event(json!({"type": "content_block_start", "index": 0})),
+            event(json!({"type": "ping"})),
+            delta("Hello"),
+            delta(", world"),
+            event(json!({"type": "content_block_stop", "index": 0})),
+            event(json!({"type": "message_stop"})),
+            delta(" after the end"),
+        ])
+        .await;
+
+        assert_eq!(result.unwrap(), "Hello, world");
+        assert_eq!(pieces, ["Hello", ", world"]);
+        let request = &server.requests()[0];
+        assert_eq!(request.headers["x-api-key"], "test-key");
+        assert_eq!(request.body["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn stream_error_events_fail_the_request() {
+        let (_, pieces, result) = stream(&[
+            delta("Partial"),
+            event(json!({
+                "type": "error",
+                "error": {"type": "overloaded_error", "message": "Overloaded"}
+            })),
+        ])
+        .await;
+
+        assert_eq!(pieces, ["Partial"]);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Anthropic API error while streaming: Overloaded"
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use std::fmt;
+use std::time::Duration;
+
+use reqwest::StatusCode;
+
+/// An error status from an LLM API
+#[derive(Debug)]
+pub struct ApiError {
+    /// Provider name, e.g. "Anthropic"
+    pub provider: String,
+    pub status: StatusCode,
+    /// Identifier the provider assigned to the request, for support tickets
+    pub request_id: Option<String>,
+    /// The error message from the response body, or the whole body
+    pub message: String,
+    /// How long the server asked us to wait before trying again
+    pub retry_after: Option<Duration>,
+}
+
+impl ApiError {
+    /// Read the error from a response with an error status
+    pub(super) async fn from_response(provider: &str, response: reqwest::Response) -> Self {
+        let status = response.status();
+        let headers = response.headers();
+        // Anthropic sends request-id, OpenAI and most compatible servers x-request-id
+        let request_id = ["request-id", "x-request-id"]
+            .iter()
+            .find_map(|name| headers.get(*name)?.to_str().ok())
+            .map(str::to_string);
+        let retry_after = retry_after(headers);
+        let body = response.text().await.unwrap_or_default();
+
+        Self {
+            provider: provider.to_string(),
+            status,
+            request_id,
+            message: error_message(&body).unwrap_or(body),
+            retry_after,
+        }
+    }
+
+    /// Whether the same request may succeed later: rate limits, overload and
+    /// server errors, but not authentication or invalid requests
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.status.as_u16(),
+            // 529 is Anthropic's "overloaded"
+            408 | 409 | 429 | 500..=599
+        )
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} API error ({}", self.provider, self.status.as_u16())?;
+        // Anthropic's 529 "overloaded" has no standard reason phrase
+        if let Some(reason) = self.status.canonical_reason() {
+            write!(f, " {}", reason)?;
+        }
+        if let Some(ref request_id) = self.request_id {
+            write!(f, ", request id {}", request_id)?;
+        }
+        write!(f, "): {}", self.message)
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+/// `retry-after-ms` (OpenAI) or `retry-after` in seconds or as an HTTP date
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name| headers.get(name)?.to_str().ok();
+
+    if let Some(ms) = header("retry-after-ms").and_then(|v| v.trim().parse::<f64>().ok()) {
+        return Duration::try_from_secs_f64(ms / 1000.0).ok();
+    }
+    let value = header("retry-after")?.trim();
+    if let Ok(secs) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(secs).ok();
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    (date.with_timezone(&chrono::Utc) - chrono::Utc::now())
+        .to_std()
+        .ok()
+}
+
+/// The message in the JSON error bodies of Anthropic (`error.message`),
+/// OpenAI (`error.message`) and Ollama (`error`)
+fn error_message(body: &str) -> Option<String> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    let error = json.get("error")?;
+    error
+        .get("message")
+        .unwrap_or(error)
+        .as_str()
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderMap, HeaderValue};
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, HeaderValue::from_str(value).unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn retry_after_forms() {
+        assert_eq!(
+            retry_after(&headers(&[("retry-after", "2")])),
+            Some(Duration::from_secs(2))
+        );
+        assert_eq!(
+            retry_after(&headers(&[
+                ("retry-after-ms", "1500"),
+                ("retry-after", "9")
+            ])),
+            Some(Duration::from_millis(1500))
+        );
+        assert_eq!(
+            retry_after(&headers(&[(
+                "retry-after",
+                "Wed, 21 Oct 2015 07:28:00 GMT"
+            )])),
+            None,
+            "dates in the past mean no wait"
+        );
+        let later = (chrono::Utc::now() + chrono::Duration::seconds(30)).to_rfc2822();
+        let wait = retry_after(&headers(&[("retry-after", &later)])).unwrap();
+        assert!(wait > Duration::from_secs(25) && wait <= Duration::from_secs(30));
+        assert_eq!(retry_after(&headers(&[("retry-after", "soon")])), None);
+        assert_eq!(retry_after(&HeaderMap::new()), None);
+    }
+
+    #[test]
+    fn error_message_forms() {
+        assert_eq!(
+            error_message(
+                r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#
+            )
+            .as_deref(),
+            Some("Overloaded")
+        );
+        assert_eq!(
+            error_message(r#"{"error":"model 'llama9' not found"}"#).as_deref(),
+            Some("model 'llama9' not found")
+        );
+        assert_eq!(error_message("<html>Bad Gateway</html>"), None);
+    }
+
+    #[test]
+    fn retryable_statuses() {
+        let error = |status: u16| ApiError {
+            provider: "Test".to_string(),
+            status: StatusCode::from_u16(status).unwrap(),
+            request_id: None,
+            message: String::new(),
+            retry_after: None,
+        };
+        for status in [408, 409, 429, 500, 502, 529] {
+            assert!(error(status).is_retryable(), "{}", status);
+        }
+        for status in [400, 401, 403, 404, 413] {
+            assert!(!error(status).is_retryable(), "{}", status);
+        }
+        assert_eq!(error(529).to_string(), "Test API error (529): ");
+    }
+}
@@ -0,0 +1,331 @@
+mod anthropic;
+mod error;
+mod models;
+mod ollama;
+mod openai;
+mod stream;
+#[cfg(test)]
+mod test_server;
+
+pub use anthropic::AnthropicProvider;
+pub use error::ApiError;
+pub use ollama::OllamaProvider;
+pub use openai::OpenAiProvider;
+
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::pin::Pin;
+use std::time::Duration;
+
+use crate::cli::Provider;
+
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// A chat model behind an HTTP API.
+///
+/// Implementations send one user message and return the text of the reply;
+/// prompts are built by `Summarizer`.
+pub trait LlmProvider: Send + Sync {
+    /// Name for messages, e.g. "Anthropic"
+    fn name(&self) -> &str;
+
+    fn model(&self) -> &str;
+
+    /// Tokens reserved for the reply
+    fn max_output_tokens(&self) -> u32;
+
+    /// Largest prompt the model takes, leaving room for the reply
+    fn max_input_tokens(&self) -> usize {
+        models::context_window(self.model()).saturating_sub(self.max_output_tokens() as usize)
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String>>;
+
+    /// Like `complete`, also passing the reply to `on_text` piece by piece
+    /// as it arrives
+    fn complete_stream<'a>(
+        &'a self,
+        prompt: &'a str,
+        on_text: &'a mut (dyn FnMut(&str) + Send),
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let text = self.complete(prompt).await?;
+            on_text(&text);
+            Ok(text)
+        })
+    }
+}
+
+/// Approximate characters per token (conservative estimate)
+const CHARS_PER_TOKEN: f64 = 3.5;
+
+/// Estimate the number of tokens in a string
+pub fn estimate_tokens(text: &str) -> usize {
+    (text.len() as f64 / CHARS_PER_TOKEN).ceil() as usize
+}
+
+/// Reply length used when none is configured
+pub const DEFAULT_MAX_OUTPUT_TOKENS: u32 = 1024;
+
+/// Request timeout used when none is configured, in seconds
+pub const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Retries after a failed request when none are configured
+pub const DEFAULT_MAX_RETRIES: u32 = 4;
+
+/// Wait before the first retry, doubled for each one after
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// A server asking us to wait longer than this is treated as a failure
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(300);
+
+/// Settings shared by every provider; unset values fall back to the
+/// provider's defaults and environment variables
+#[derive(Debug, Clone)]
+pub struct ProviderSettings {
+    pub model: Option<String>,
+    pub max_output_tokens: u32,
+    /// API endpoint, overriding the provider's environment variable
+    pub base_url: Option<String>,
+    /// Limit on a whole request, or for streamed replies on connecting
+    /// and on each wait for more data; `None` waits for as long as the
+    /// server takes
+    pub timeout: Option<Duration>,
+    /// Proxy for every request; without it `HTTPS_PROXY`, `HTTP_PROXY` and
+    /// `NO_PROXY` apply
+    pub proxy: Option<String>,
+    /// Retries after rate limiting, overload, server and connection errors
+    pub max_retries: u32,
+}
+
+impl ProviderSettings {
+    /// `base_url` if set, else the environment variable `var`, else `default`
+    fn base_url(&self, var: &str, default: &str) -> String {
+        let url = self
+            .base_url
+            .clone()
+            .or_else(|| std::env::var(var).ok().filter(|url| !url.is_empty()))
+            .unwrap_or_else(|| default.to_string());
+        url.trim_end_matches('/').to_string()
+    }
+
+    fn client(&self) -> Result<HttpClient> {
+        let mut builder = self.client_builder()?;
+        let mut stream_builder = self.client_builder()?;
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+            // A streamed reply may take longer than the timeout as a whole,
+            // so only a stalled connection counts
+            stream_builder = stream_builder
+                .connect_timeout(timeout)
+                .read_timeout(timeout);
+        }
+        Ok(HttpClient {
+            http: builder.build().context("Failed to create HTTP client")?,
+            stream_http: stream_builder
+                .build()
+                .context("Failed to create HTTP client")?,
+            max_retries: self.max_retries,
+        })
+    }
+
+    fn client_builder(&self) -> Result<reqwest::ClientBuilder> {
+        let mut builder = reqwest::Client::builder();
+        if let Some(ref proxy) = self.proxy {
+            let proxy = reqwest::Proxy::all(proxy)
+                .with_context(|| format!("Invalid proxy URL: {}", proxy))?;
+            builder = builder.proxy(proxy);
+        }
+        Ok(builder)
+    }
+}
+
+/// Model used with `provider` when none is configured
+pub fn default_model(provider: &Provider) -> &'static str {
+    match provider {
+        Provider::Anthropic => anthropic::DEFAULT_MODEL,
+        Provider::OpenAi => openai::DEFAULT_MODEL,
+        Provider::Ollama => ollama::DEFAULT_MODEL,
+    }
+}
+
+/// HTTP client that retries requests which may succeed later
+struct HttpClient {
+    http: reqwest::Client,
+    /// For streamed replies, with the timeout applying to each read
+    stream_http: reqwest::Client,
+    max_retries: u32,
+}
+
+impl HttpClient {
+    fn post(&self, url: String, stream: bool) -> reqwest::RequestBuilder {
+        if stream {
+            self.stream_http.post(url)
+        } else {
+            self.http.post(url)
+        }
+    }
+
+    /// Send a JSON request and decode the JSON reply
+    async fn send_json<T: DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+        name: &str,
+    ) -> Result<T> {
+        self.send(request, name)
+            .await?
+            .json()
+            .await
+            .with_context(|| format!("Failed to parse {} response", name))
+    }
+
+    /// Send a request and return the response once it has a success status.
+    /// Error statuses become an `ApiError`; retryable ones are retried with
+    /// exponential backoff and jitter, or after as long as the server's
+    /// `retry-after` asks for.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        name: &str,
+    ) -> Result<reqwest::Response> {
+        let mut retries = 0;
+        loop {
+            let attempt = request
+                .try_clone()
+                .context("Failed to build request")?
+                .send()
+                .await;
+            let (error, retry_after) = match attempt {
+                Ok(response) if response.status().is_success() => return Ok(response),
+                Ok(response) => {
+                    let error = ApiError::from_response(name, response).await;
+                    if !error.is_retryable() {
+                        return Err(error.into());
+                    }
+                    let retry_after = error.retry_after;
+                    (anyhow::Error::new(error), retry_after)
+                }
+                Err(e) if e.is_connect() || e.is_timeout() => (request_error(e, name), None),
+                Err(e) => return Err(request_error(e, name)),
+            };
+
+            let delay = retry_after.unwrap_or_else(|| backoff(retries));
+            if retries == self.max_retries || delay > MAX_RETRY_AFTER {
+                return Err(if retries == 0 {
+                    error
+                } else {
+                    error.context(format!(
+                        "{} API request failed after {} attempts",
+                        name,
+                        retries + 1
+                    ))
+                });
+            }
+            retries += 1;
+            eprintln!(
+                "{:#}; retrying in {:.1}s ({} of {})...",
+                error,
+                delay.as_secs_f64(),
+                retries,
+                self.max_retries
+            );
+            tokio::time::sleep(delay).await;
+        }
+    }
+}
+
+/// Exponential backoff with jitter: between half and all of
+/// `INITIAL_BACKOFF * 2^retries`, capped at `MAX_BACKOFF`
+fn backoff(retries: u32) -> Duration {
+    let backoff = INITIAL_BACKOFF
+        .saturating_mul(2u32.saturating_pow(retries))
+        .min(MAX_BACKOFF);
+    backoff.mul_f64(0.5 + fastrand::f64() / 2.0)
+}
+
+fn request_error(error: reqwest::Error, name: &str) -> anyhow::Error {
+    if error.is_timeout() {
+        anyhow::anyhow!("{} API request timed out (see --timeout)", name)
+    } else {
+        anyhow::Error::new(error).context(format!("Failed to send request to {} API", name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use test_server::{Reply, TestServer};
+
+    async fn send(server: &TestServer) -> Result<Value> {
+        let client = server.settings("model").client().unwrap();
+        client
+            .send_json(
+                client.post(server.url.clone(), false).json(&json!({})),
+                "Test",
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn overload_is_retried() {
+        let server = TestServer::start(vec![
+            Reply::status(529, json!({"error": {"message": "Overloaded"}}))
+                .header("retry-after", "0"),
+            Reply::json(json!({"ok": true})),
+        ])
+        .await;
+
+        assert_eq!(send(&server).await.unwrap(), json!({"ok": true}));
+        assert_eq!(server.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_are_limited() {
+        let overloaded = || Reply::status(503, json!({})).header("retry-after", "0");
+        let server = TestServer::start(vec![overloaded(), overloaded(), overloaded()]).await;
+
+        let error = send(&server).await.unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "Test API request failed after 3 attempts"
+        );
+        assert_eq!(error.downcast_ref::<ApiError>().unwrap().status, 503);
+    }
+
+    #[tokio::test]
+    async fn authentication_errors_fail_at_once() {
+        let server = TestServer::start(vec![Reply::status(
+            401,
+            json!({"error": {"type": "authentication_error", "message": "invalid x-api-key"}}),
+        )
+        .header("request-id", "req_123")])
+        .await;
+
+        let error = send(&server).await.unwrap_err();
+        let error = error.downcast_ref::<ApiError>().unwrap();
+
+        assert_eq!(error.status, 401);
+        assert_eq!(error.request_id.as_deref(), Some("req_123"));
+        assert_eq!(
+            error.to_string(),
+            "Test API error (401 Unauthorized, request id req_123): invalid x-api-key"
+        );
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[test]
+    fn backoff_grows_with_jitter() {
+        for retries in 0..10 {
+            let full = INITIAL_BACKOFF
+                .saturating_mul(2u32.pow(retries))
+                .min(MAX_BACKOFF);
+            let delay = backoff(retries);
+            assert!(delay >= full / 2 && delay <= full, "{:?}", delay);
+        }
+    }
+}
@@ -0,0 +1,69 @@
+/// Context windows, in tokens, of well-known models. Names are matched by
+/// prefix, so more specific entries come first.
+const CONTEXT_WINDOWS: &[(&str, usize)] = &[
+    // Anthropic
+    ("claude-", 200_000),
+    // OpenAI
+    ("gpt-5", 400_000),
+    ("gpt-4.1", 1_047_576),
+    ("gpt-4o", 128_000),
+    ("gpt-4-turbo", 128_000),
+    ("gpt-4", 8_192),
+    ("gpt-3.5-turbo", 16_385),
+    ("o1", 200_000),
+    ("o3", 200_000),
+    ("o4-mini", 200_000),
+    // Open-weight models, as named by Ollama and Hugging Face
+    ("llama3.1", 128_000),
+    ("llama3.2", 128_000),
+    ("llama3.3", 128_000),
+    ("llama-3.1", 128_000),
+    ("llama-3.2", 128_000),
+    ("llama-3.3", 128_000),
+    ("llama3", 8_192),
+    ("qwen2.5", 32_768),
+    ("qwen3", 40_960),
+    ("mistral", 32_768),
+    ("mixtral", 32_768),
+    ("gemma3", 128_000),
+    ("gemma2", 8_192),
+    ("deepseek", 128_000),
+    ("phi4", 16_384),
+    ("phi3", 128_000),
+];
+
+/// Assumed for models missing from the table
+const DEFAULT_CONTEXT_WINDOW: usize = 32_768;
+
+/// Context window of `model`, ignoring an organization prefix such as
+/// "meta-llama/"
+pub fn context_window(model: &str) -> usize {
+    let name = model.rsplit('/').next().unwrap_or(model).to_lowercase();
+    CONTEXT_WINDOWS
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map_or(DEFAULT_CONTEXT_WINDOW, |(_, tokens)| *tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn most_specific_prefix_wins() {
+        assert_eq!(context_window("gpt-4o-mini"), 128_000);
+        assert_eq!(context_window("gpt-4-0613"), 8_192);
+        assert_eq!(context_window("llama3.1:70b"), 128_000);
+        assert_eq!(context_window("llama3:8b"), 8_192);
+    }
+
+    #[test]
+    fn organization_prefix_and_case_are_ignored() {
+        assert_eq!(context_window("meta-llama/Llama-3.3-70B-Instruct"), 128_000);
+    }
+
+    #[test]
+    fn unknown_models_get_the_default() {
+        assert_eq!(context_window("my-finetune"), DEFAULT_CONTEXT_WINDOW);
+    }
+}
@@ -0,0 +1,267 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::stream::Lines;
+use super::{estimate_tokens, models, BoxFuture, HttpClient, LlmProvider, ProviderSettings};
+
+pub const DEFAULT_MODEL: &str = "llama3.1";
+
+const DEFAULT_HOST: &str = "http://localhost:11434";
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    messages: Vec<Message<'a>>,
+    stream: bool,
+    options: Options,
+}
+
+#[derive(Debug, Serialize)]
+struct Options {
+    num_predict: u32,
+    /// Ollama truncates prompts to a small default context unless told
+    num_ctx: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    message: ReplyMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplyMessage {
+    content: String,
+}
+
+/// One line of a streamed reply, which Ollama sends as JSON lines rather
+/// than server-sent events
+#[derive(Debug, Deserialize)]
+struct ChatChunk {
+    message: Option<ReplyMessage>,
+    #[serde(default)]
+    done: bool,
+    error: Option<String>,
+}
+
+/// Smallest context requested, Ollama's own default
+const MIN_CONTEXT: usize = 4096;
+
+/// Context to request for `prompt`: room for the prompt and the reply,
+/// rounded up to a power of two so that similar requests don't make Ollama
+/// reload the model, and capped at the model's window. Ollama allocates
+/// memory for the whole context, so asking for the full window of a 128k
+/// model would exhaust most machines.
+fn context_size(prompt: &str, max_output_tokens: u32, model: &str) -> usize {
+    let needed = estimate_tokens(prompt) + max_output_tokens as usize;
+    needed
+        .next_power_of_two()
+        .max(MIN_CONTEXT)
+        .min(models::context_window(model))
+}
+
+/// A model served by a local or self-hosted Ollama
+pub struct OllamaProvider {
+    host: String,
+    model: String,
+    max_output_tokens: u32,
+    client: HttpClient,
+}
+
+impl OllamaProvider {
+    /// Read the server from `OLLAMA_HOST` unless configured (default
+    /// `http://localhost:11434`)
+    pub fn from_env(settings: &ProviderSettings) -> Result<Self> {
+        let host = settings.base_url("OLLAMA_HOST", DEFAULT_HOST);
+        // Ollama itself accepts a bare "host:port"
+        let host = if host.contains("://") {
+            host
+        } else {
+            format!("http://{}", host)
+        };
+
+        Ok(Self {
+            host,
+            model: settings
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            max_output_tokens: settings.max_output_tokens,
+            client: settings.client()?,
+        })
+    }
+
+    fn request(&self, prompt: &str, stream: bool) -> reqwest::RequestBuilder {
+        let request = ChatRequest {
+            model: &self.model,
+            messages: vec![Message {
+                role: "user",
+                content: prompt,
+            }],
+            stream,
+            options: Options {
+                num_predict: self.max_output_tokens,
+                num_ctx: context_size(prompt, self.max_output_tokens, &self.model),
+            },
+        };
+
+        self.client
+            .post(format!("{}/api/chat", self.host), stream)
+            .json(&request)
+    }
+}
+
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &str {
+        "Ollama"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn max_output_tokens(&self) -> u32 {
+        self.max_output_tokens
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let response: ChatResponse = self
+                .client
+                .send_json(self.request(prompt, false), self.name())
+                .await?;
+            Ok(response.message.content)
+        })
+    }
+
+    fn complete_stream<'a>(
+        &'a self,
+        prompt: &'a str,
+        on_text: &'a mut (dyn FnMut(&str) + Send),
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .send(self.request(prompt, true), self.name())
+                .await?;
+
+            let mut lines = Lines::new(response, self.name());
+            let mut text = String::new();
+            while let Some(line) = lines.next_line().await? {
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let chunk: ChatChunk = serde_json::from_str(&line)
+                    .with_context(|| format!("Failed to parse {} stream line", self.name()))?;
+                if let Some(error) = chunk.error {
+                    anyhow::bail!("{} API error while streaming: {}", self.name(), error);
+                }
+                if let Some(message) = chunk.message {
+                    on_text(&message.content);
+                    text.push_str(&message.content);
+                }
+                if chunk.done {
+                    break;
+                }
+            }
+            Ok(text)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::test_server::{Reply, TestServer};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn complete_posts_chat() {
+        let server = TestServer::start(vec![Reply::json(json!({
+            "message": {"role": "assistant", "content": "Summary"},
+            "done": true
+        }))])
+        .await;
+        let provider = OllamaProvider::from_env(&server.settings("llama3.1")).unwrap();
+
+        assert_eq!(provider.complete("Prompt").await.unwrap(), "Summary");
+
+        let request = &server.requests()[0];
+        assert_eq!(request.path, "/api/chat");
+        assert_eq!(request.body["stream"], false);
+        assert_eq!(request.body["options"]["num_predict"], 256);
+        assert_eq!(request.body["options"]["num_ctx"], MIN_CONTEXT);
+    }
+
+    #[tokio::test]
+    async fn stream_reads_json_lines() {
+        let server = TestServer::start(vec![Reply::stream(
+            "application/x-ndjson",
+            &[
+                b"{\"message\":{\"role\":\"assistant\",\"content\":\"Caf\xc3",
+                b"\xa9 \"},\"done\":false}\n{\"message\":{\"role\":\"assistant\",",
+                b"\"content\":\"ok\"},\"done\":false}\n{\"done\":true}\n",
+            ],
+        )])
+        .await;
+        let provider = OllamaProvider::from_env(&server.settings("llama3.1")).unwrap();
+
+        let mut pieces = Vec::new();
+        let text = provider
+            .complete_stream("Prompt", &mut |piece| pieces.push(piece.to_string()))
+            .await
+            .unwrap();
+
+        assert_eq!(pieces, ["Caf\u{e9} ", "ok"]);
+        assert_eq!(text, "Caf\u{e9} ok");
+        assert_eq!(server.requests()[0].body["stream"], true);
+    }
+
+    #[tokio::test]
+    async fn stream_errors_are_reported() {
+        let server = TestServer::start(vec![Reply::stream(
+            "application/x-ndjson",
+            &[b"{\"error\":\"model ran out of memory\"}\n"],
+        )])
+        .await;
+        let provider = OllamaProvider::from_env(&server.settings("llama3.1")).unwrap();
+
+        let error = provider
+            .complete_stream("Prompt", &mut |_| {})
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Ollama API error while streaming: model ran out of memory"
+        );
+    }
+
+    #[test]
+    fn context_fits_prompt_and_reply() {
+        assert_eq!(context_size("short", 1024, "llama3.1"), MIN_CONTEXT);
+        // ~10k tokens of prompt and 1k of reply
+        assert_eq!(context_size(&"x".repeat(35_000), 1024, "llama3.1"), 16_384);
+        // Never more than the model's window
+        assert_eq!(context_size(&"x".repeat(35_000), 1024, "llama3:8b"), 8_192);
+    }
+
+    #[test]
+    fn host_without_scheme_gets_http() {
+        let settings = ProviderSettings {
+            model: None,
+            max_output_tokens: 256,
+            base_url: Some("gpu-box:11434".to_string()),
+            timeout: None,
+            proxy: None,
+            max_retries: 0,
+        };
+        let provider = OllamaProvider::from_env(&settings).unwrap();
+        assert_eq!(provider.host, "http://gpu-box:11434");
+        assert_eq!(provider.model, DEFAULT_MODEL);
+    }
+}
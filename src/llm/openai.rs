@@ -0,0 +1,279 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::stream::Events;
+use super::{BoxFuture, HttpClient, LlmProvider, ProviderSettings};
+
+pub const DEFAULT_MODEL: &str = "gpt-4o";
+
+const DEFAULT_BASE_URL: &str = "https://api.openai.com/v1";
+
+/// Model families that reject `max_tokens` wherever they are served
+const COMPLETION_TOKENS_MODELS: &[&str] = &["o1", "o3", "o4", "gpt-5"];
+
+#[derive(Debug, Serialize)]
+struct ChatRequest<'a> {
+    model: &'a str,
+    /// Reply length for generic OpenAI-compatible servers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_tokens: Option<u32>,
+    /// Reply length for OpenAI itself, whose reasoning models reject
+    /// `max_tokens`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_completion_tokens: Option<u32>,
+    messages: Vec<Message<'a>>,
+    stream: bool,
+}
+
+#[derive(Debug, Serialize)]
+struct Message<'a> {
+    role: &'a str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatResponse {
+    choices: Vec<Choice>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Choice {
+    message: ReplyMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReplyMessage {
+    content: Option<String>,
+}
+
+/// One server-sent event when streaming
+#[derive(Debug, Deserialize)]
+struct ChatChunk {
+    /// Empty on a final usage-only chunk
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    error: Option<ChunkError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    delta: ReplyMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkError {
+    message: String,
+}
+
+/// Any server implementing OpenAI's chat completions API: OpenAI itself,
+/// Azure-style gateways, vLLM, LM Studio, llama.cpp and the like
+pub struct OpenAiProvider {
+    /// Sent as a bearer token when set; local servers often need none
+    api_key: Option<String>,
+    base_url: String,
+    model: String,
+    max_output_tokens: u32,
+    client: HttpClient,
+}
+
+impl OpenAiProvider {
+    /// Read the API key from `OPENAI_API_KEY` and, unless configured, the
+    /// server from `OPENAI_BASE_URL` (default `https://api.openai.com/v1`)
+    pub fn from_env(settings: &ProviderSettings) -> Result<Self> {
+        Ok(Self {
+            api_key: std::env::var("OPENAI_API_KEY").ok(),
+            base_url: settings.base_url("OPENAI_BASE_URL", DEFAULT_BASE_URL),
+            model: settings
+                .model
+                .clone()
+                .unwrap_or_else(|| DEFAULT_MODEL.to_string()),
+            max_output_tokens: settings.max_output_tokens,
+            client: settings.client()?,
+        })
+    }
+
+    /// OpenAI's API and its reasoning and GPT-5 models take
+    /// `max_completion_tokens`; other servers mostly only know `max_tokens`
+    fn uses_completion_tokens(&self) -> bool {
+        let model = self.model.rsplit('/').next().unwrap_or(&self.model);
+        self.base_url.starts_with(DEFAULT_BASE_URL)
+            || COMPLETION_TOKENS_MODELS
+                .iter()
+                .any(|prefix| model.starts_with(prefix))
+    }
+
+    fn request(&self, prompt: &str, stream: bool) -> reqwest::RequestBuilder {
+        let (max_tokens, max_completion_tokens) = if self.uses_completion_tokens() {
+            (None, Some(self.max_output_tokens))
+        } else {
+            (Some(self.max_output_tokens), None)
+        };
+        let request = ChatRequest {
+            model: &self.model,
+            max_tokens,
+            max_completion_tokens,
+            messages: vec![Message {
+                role: "user",
+                content: prompt,
+            }],
+            stream,
+        };
+
+        let builder = self
+            .client
+            .post(format!("{}/chat/completions", self.base_url), stream)
+            .json(&request);
+        match self.api_key {
+            Some(ref api_key) => builder.bearer_auth(api_key),
+            None => builder,
+        }
+    }
+}
+
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &str {
+        "OpenAI-compatible"
+    }
+
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn max_output_tokens(&self) -> u32 {
+        self.max_output_tokens
+    }
+
+    fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let response: ChatResponse = self
+                .client
+                .send_json(self.request(prompt, false), self.name())
+                .await?;
+
+            response
+                .choices
+                .into_iter()
+                .next()
+                .and_then(|c| c.message.content)
+                .context("No content in response")
+        })
+    }
+
+    fn complete_stream<'a>(
+        &'a self,
+        prompt: &'a str,
+        on_text: &'a mut (dyn FnMut(&str) + Send),
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let response = self
+                .client
+                .send(self.request(prompt, true), self.name())
+                .await?;
+
+            let mut events = Events::new(response, self.name());
+            let mut text = String::new();
+            while let Some(data) = events.next_data().await? {
+                if data == "[DONE]" {
+                    break;
+                }
+                let chunk: ChatChunk = serde_json::from_str(&data)
+                    .with_context(|| format!("Failed to parse {} stream event", self.name()))?;
+                if let Some(error) = chunk.error {
+                    anyhow::bail!(
+                        "{} API error while streaming: {}",
+                        self.name(),
+                        error.message
+                    );
+                }
+                let delta = chunk
+                    .choices
+                    .into_iter()
+                    .next()
+                    .and_then(|c| c.delta.content);
+                if let Some(delta) = delta {
+                    on_text(&delta);
+                    text.push_str(&delta);
+                }
+            }
+            Ok(text)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::test_server::{Reply, TestServer};
+    use serde_json::json;
+
+    fn reply(text: &str) -> Reply {
+        Reply::json(json!({
+            "choices": [{"message": {"role": "assistant", "content": text}}]
+        }))
+    }
+
+    #[tokio::test]
+    async fn complete_posts_chat_completion() {
+        let server = TestServer::start(vec![reply("Summary")]).await;
+        let provider = OpenAiProvider::from_env(&server.settings("llama-3.3-70b")).unwrap();
+
+        assert_eq!(provider.complete("Prompt").await.unwrap(), "Summary");
+
+        let requests = server.requests();
+        assert_eq!(requests[0].path, "/chat/completions");
+        let body = &requests[0].body;
+        assert_eq!(body["model"], "llama-3.3-70b");
+        assert_eq!(body["max_tokens"], 256);
+        assert!(body.get("max_completion_tokens").is_none());
+        assert_eq!(body["messages"][0]["content"], "Prompt");
+    }
+
+    #[tokio::test]
+    async fn reasoning_models_get_max_completion_tokens() {
+        let server = TestServer::start(vec![reply("Summary")]).await;
+        let provider = OpenAiProvider::from_env(&server.settings("o4-mini")).unwrap();
+
+        provider.complete("Prompt").await.unwrap();
+
+        let body = &server.requests()[0].body;
+        assert_eq!(body["max_completion_tokens"], 256);
+        assert!(body.get("max_tokens").is_none());
+    }
+
+    #[tokio::test]
+    async fn missing_content_is_an_error() {
+        let server = TestServer::start(vec![Reply::json(json!({"choices": []}))]).await;
+        let provider = OpenAiProvider::from_env(&server.settings("gpt-4o")).unwrap();
+
+        assert!(provider.complete("Prompt").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn stream_may_outlast_the_timeout() {
+        let events: Vec<Vec<u8>> = (0..40)
+            .map(|i| {
+                let chunk = json!({"choices": [{"delta": {"content": format!("{} ", i)}}]});
+                format!("data: {}\n\n", chunk).into_bytes()
+            })
+            .chain([b"data: [DONE]\n\n".to_vec()])
+            .collect();
+        let chunks: Vec<&[u8]> = events.iter().map(Vec::as_slice).collect();
+        let server = TestServer::start(vec![Reply::stream("text/event-stream", &chunks)]).await;
+        // The server pauses between chunks, taking longer than this overall
+        let settings = ProviderSettings {
+            timeout: Some(std::time::Duration::from_millis(100)),
+            ..server.settings("gpt-4o")
+        };
+        let provider = OpenAiProvider::from_env(&settings).unwrap();
+
+        let mut pieces = 0;
+        let text = provider
+            .complete_stream("Prompt", &mut |_| pieces += 1)
+            .await
+            .unwrap();
+
+        assert_eq!(pieces, 40);
+        assert!(text.starts_with("0 1 2 ") && text.ends_with("39 "));
+        assert_eq!(server.requests()[0].body["stream"], true);
+    }
+}
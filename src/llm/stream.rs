@@ -0,0 +1,142 @@
+use anyhow::Result;
+
+use super::request_error;
+
+/// The body of a streamed response, line by line as it arrives
+pub(super) struct Lines<'a> {
+    response: reqwest::Response,
+    /// Provider name for errors
+    name: &'a str,
+    /// Bytes received after the last complete line, which may end inside
+    /// a multi-byte character
+    buffer: Vec<u8>,
+}
+
+impl<'a> Lines<'a> {
+    pub(super) fn new(response: reqwest::Response, name: &'a str) -> Self {
+        Self {
+            response,
+            name,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// The next line without its line ending, or `None` at the end of the body
+    pub(super) async fn next_line(&mut self) -> Result<Option<String>> {
+        loop {
+            if let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = self.buffer.drain(..=end).collect();
+                return Ok(Some(decode(&line)));
+            }
+            let chunk = self.response.chunk().await.map_err(|e| {
+                if e.is_timeout() {
+                    anyhow::anyhow!(
+                        "{} API stopped sending the reply (see --timeout)",
+                        self.name
+                    )
+                } else {
+                    request_error(e, self.name)
+                }
+            })?;
+            match chunk {
+                Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                None if self.buffer.is_empty() => return Ok(None),
+                None => return Ok(Some(decode(&std::mem::take(&mut self.buffer)))),
+            }
+        }
+    }
+}
+
+/// Server-sent events, as used by the Anthropic and OpenAI streaming APIs
+pub(super) struct Events<'a> {
+    lines: Lines<'a>,
+}
+
+impl<'a> Events<'a> {
+    pub(super) fn new(response: reqwest::Response, name: &'a str) -> Self {
+        Self {
+            lines: Lines::new(response, name),
+        }
+    }
+
+    /// The data of the next event, or `None` at the end of the stream.
+    /// Event names, ids and comments are skipped; both APIs repeat the
+    /// event type inside the data.
+    pub(super) async fn next_data(&mut self) -> Result<Option<String>> {
+        let mut data: Option<String> = None;
+        while let Some(line) = self.lines.next_line().await? {
+            if line.is_empty() {
+                // A blank line ends the event
+                if data.is_some() {
+                    return Ok(data);
+                }
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("data:") {
+                let value = value.strip_prefix(' ').unwrap_or(value);
+                match data {
+                    Some(ref mut data) => {
+                        data.push('\n');
+                        data.push_str(value);
+                    }
+                    None => data = Some(value.to_string()),
+                }
+            }
+        }
+        Ok(data)
+    }
+}
+
+fn decode(line: &[u8]) -> String {
+    String::from_utf8_lossy(line)
+        .trim_end_matches(['\r', '\n'])
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::llm::test_server::{Reply, TestServer};
+
+    async fn response(chunks: &[&[u8]]) -> reqwest::Response {
+        let server = TestServer::start(vec![Reply::stream("text/event-stream", chunks)]).await;
+        reqwest::get(&server.url).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn lines_are_joined_across_chunks() {
+        // "é" is split between chunks
+        let response = response(&[b"one\r\ntw", b"o \xc3", b"\xa9\nthree"]).await;
+        let mut lines = Lines::new(response, "Test");
+
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("one"));
+        assert_eq!(
+            lines.next_line().await.unwrap().as_deref(),
+            Some("two \u{e9}")
+        );
+        assert_eq!(lines.next_line().await.unwrap().as_deref(), Some("three"));
+        assert_eq!(lines.next_line().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn events_yield_data() {
+        let response = response(&[
+            b": keep-alive\n\nevent: delta\ndata: {\"a\":1}\n\n",
+            b"data: first\ndata:second\n\nid: 3\n\n",
+            b"data: [DONE]",
+        ])
+        .await;
+        let mut events = Events::new(response, "Test");
+
+        assert_eq!(
+            events.next_data().await.unwrap().as_deref(),
+            Some("{\"a\":1}")
+        );
+        assert_eq!(
+            events.next_data().await.unwrap().as_deref(),
+            Some("first\nsecond")
+        );
+        assert_eq!(events.next_data().await.unwrap().as_deref(), Some("[DONE]"));
+        assert_eq!(events.next_data().await.unwrap(), None);
+    }
+}
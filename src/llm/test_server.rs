@@ -0,0 +1,150 @@
+//! A local stand-in for LLM APIs, for testing providers over real HTTP
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use super::ProviderSettings;
+
+/// A request the server received
+#[derive(Debug, Clone)]
+pub struct Request {
+    pub path: String,
+    /// Header names are lowercase
+    pub headers: HashMap<String, String>,
+    pub body: serde_json::Value,
+}
+
+/// A canned response
+pub struct Reply {
+    status: u16,
+    headers: Vec<(String, String)>,
+    /// Written one after another with a pause in between, so the client
+    /// sees them as separate chunks
+    chunks: Vec<Vec<u8>>,
+}
+
+impl Reply {
+    pub fn json(body: serde_json::Value) -> Self {
+        Self::status(200, body)
+    }
+
+    pub fn status(status: u16, body: serde_json::Value) -> Self {
+        Self {
+            status,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            chunks: vec![body.to_string().into_bytes()],
+        }
+    }
+
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// A 200 response sent in `chunks`
+    pub fn stream(content_type: &str, chunks: &[&[u8]]) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("content-type".to_string(), content_type.to_string())],
+            chunks: chunks.iter().map(|chunk| chunk.to_vec()).collect(),
+        }
+    }
+}
+
+/// Serves one canned reply per connection, in order
+pub struct TestServer {
+    pub url: String,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+impl TestServer {
+    pub async fn start(replies: Vec<Reply>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            for reply in replies {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let request = read_request(&mut socket).await;
+                recorded.lock().unwrap().push(request);
+
+                let mut head = format!("HTTP/1.1 {} Test\r\nconnection: close\r\n", reply.status);
+                for (name, value) in &reply.headers {
+                    head.push_str(&format!("{}: {}\r\n", name, value));
+                }
+                head.push_str("\r\n");
+                socket.write_all(head.as_bytes()).await.unwrap();
+                for chunk in reply.chunks {
+                    socket.write_all(&chunk).await.unwrap();
+                    socket.flush().await.unwrap();
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+                socket.shutdown().await.ok();
+            }
+        });
+
+        Self { url, requests }
+    }
+
+    pub fn requests(&self) -> Vec<Request> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Settings pointing a provider at this server
+    pub fn settings(&self, model: &str) -> ProviderSettings {
+        ProviderSettings {
+            model: Some(model.to_string()),
+            max_output_tokens: 256,
+            base_url: Some(self.url.clone()),
+            timeout: Some(Duration::from_secs(10)),
+            proxy: None,
+            max_retries: 2,
+        }
+    }
+}
+
+async fn read_request(socket: &mut tokio::net::TcpStream) -> Request {
+    let mut data = Vec::new();
+    let mut buffer = [0; 4096];
+    let header_end = loop {
+        let read = socket.read(&mut buffer).await.unwrap();
+        data.extend_from_slice(&buffer[..read]);
+        if let Some(end) = data.windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&data[..header_end]).into_owned();
+    let mut lines = head.lines();
+    let path = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .unwrap_or_default()
+        .to_string();
+    let headers: HashMap<String, String> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim().to_lowercase(), value.trim().to_string()))
+        .collect();
+
+    let length: usize = headers
+        .get("content-length")
+        .and_then(|l| l.parse().ok())
+        .unwrap_or(0);
+    while data.len() < header_end + length {
+        let read = socket.read(&mut buffer).await.unwrap();
+        data.extend_from_slice(&buffer[..read]);
+    }
+
+    Request {
+        path,
+        headers,
+        body: serde_json::from_slice(&data[header_end..header_end + length])
+            .unwrap_or(serde_json::Value::Null),
+    }
+}
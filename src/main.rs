@@ -1,59 +1,379 @@
+mod changelog;
 mod cli;
+mod config;
+mod date;
 mod formatters;
 mod git;
+mod llm;
+mod release;
 mod summarizer;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::Parser;
+use std::io::Write;
+use std::path::PathBuf;
 
-use cli::{Args, OutputFormat};
+use cli::{Args, Backend, CoAuthorCredit, Command, ConfigAction, GroupBy, OutputFormat, Provider};
+use config::{Config, LayeredConfig, LlmConfig};
 use formatters::{Formatter, JsonFormatter, MarkdownFormatter, PrettyFormatter};
-use git::GitRepo;
+use git::{GitBackend, GitRepo, GitSummaryData, SubprocessBackend, SummaryOptions};
+use release::ReleaseNotes;
 use summarizer::Summarizer;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let args = Args::parse();
 
-    // Initialize git repo
-    let repo = GitRepo::new(&args.repo);
+    // Layer the user config, the project config of the first repository
+    // given and the command line
+    let first = args.repo.first().map(String::as_str).unwrap_or(".");
+    let root = open_backend(&args.backend, first)
+        .ok()
+        .and_then(|backend| GitRepo::new(backend).workdir().ok().flatten())
+        .unwrap_or_else(|| PathBuf::from(first));
+    let layers = LayeredConfig::load(args.config.as_deref(), Some(&root), args.profile.as_deref())?
+        .with_cli(cli_config(&args));
+
+    if let Some(Command::Config {
+        action: ConfigAction::Show,
+    }) = args.command
+    {
+        print!("{}", layers.show()?);
+        return Ok(());
+    }
+    let config = layers.merged();
+
+    // Repositories: --repo and --scan, else the config's list, else the current directory
+    let mut repo_paths = args.repo.clone();
+    if let Some(ref dir) = args.scan {
+        let found = git::discover_repos(dir)?;
+        if found.is_empty() {
+            anyhow::bail!("No git repositories found in {}", dir.display());
+        }
+        repo_paths.extend(found.iter().map(|p| p.to_string_lossy().into_owned()));
+    }
+    if repo_paths.is_empty() {
+        repo_paths = config
+            .repos
+            .iter()
+            .flatten()
+            .map(|p| p.to_string_lossy().into_owned())
+            .collect();
+    }
+    if repo_paths.is_empty() {
+        repo_paths.push(".".to_string());
+    }
+
+    // Initialize git repos
+    let repos = repo_paths
+        .iter()
+        .map(|path| Ok(GitRepo::new(open_backend(&args.backend, path)?)))
+        .collect::<Result<Vec<_>>>()?;
+
+    if let Some(Command::ReleaseNotes {
+        ref range,
+        dry,
+        ref repo_url,
+    }) = args.command
+    {
+        let [ref repo] = repos[..] else {
+            anyhow::bail!("release-notes works on a single repository");
+        };
+        let range = repo.release_range(range.as_deref());
+        let version = Some(range.to.clone()).filter(|to| to != "HEAD");
+        let options = SummaryOptions {
+            branch: range.to_string(),
+            ..base_options(&args, &config)
+        };
+        let data = repo.collect_summary(&options)?;
+        if data.commits.is_empty() {
+            eprintln!("No commits found in {}.", data.branch);
+            return Ok(());
+        }
+
+        let web_url = match repo_url {
+            Some(url) => Some(url.trim_end_matches('/').to_string()),
+            None => repo.web_url()?,
+        };
+        let notes = ReleaseNotes::new(&data, version, web_url);
+        let prose = if dry {
+            None
+        } else {
+            Some(summarizer(&config)?.summarize_release(&notes).await?)
+        };
+        println!("{}", notes.to_markdown(prose.as_deref()));
+        return Ok(());
+    }
+
+    if let Some(Command::Changelog {
+        ref range,
+        ref release,
+        ref file,
+        check,
+        ref repo_url,
+    }) = args.command
+    {
+        let [ref repo] = repos[..] else {
+            anyhow::bail!("changelog works on a single repository");
+        };
+        let range = repo.release_range(range.as_deref());
+        let options = SummaryOptions {
+            branch: range.to_string(),
+            ..base_options(&args, &config)
+        };
+        let data = repo.collect_summary(&options)?;
+
+        // Date a version by its newest commit so reruns produce the same heading
+        let date = data
+            .commits
+            .first()
+            .map(|c| c.commit_timestamp.chars().take(10).collect::<String>())
+            .unwrap_or_default();
+        let target = match release.as_deref() {
+            Some("next") => changelog::Target::version(&repo.next_version(&range.to)?.next, &date),
+            Some(version) => changelog::Target::version(version, &date),
+            None if range.to != "HEAD" => changelog::Target::version(&range.to, &date),
+            None => changelog::Target::Unreleased,
+        };
+
+        let path = match file {
+            Some(file) => file.clone(),
+            None => repo
+                .workdir()?
+                .context("No working tree to find CHANGELOG.md in; pass --file")?
+                .join("CHANGELOG.md"),
+        };
+        let existing = match std::fs::read_to_string(&path) {
+            Ok(text) => Some(text),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e).with_context(|| format!("Failed to read {}", path.display())),
+        };
+
+        let web_url = match repo_url {
+            Some(url) => Some(url.trim_end_matches('/').to_string()),
+            None => repo.web_url()?,
+        };
+        let notes = ReleaseNotes::new(&data, None, web_url);
+        let update = changelog::update(existing.as_deref(), &target, &notes);
+
+        if update.is_unchanged() {
+            eprintln!("{} is up to date.", path.display());
+        } else if check {
+            anyhow::bail!(
+                "{} is missing {} entries under {}",
+                path.display(),
+                update.added + update.moved,
+                target
+            );
+        } else {
+            std::fs::write(&path, &update.text)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!(
+                "Updated {}: {} new entries and {} moved from [Unreleased] under {}.",
+                path.display(),
+                update.added,
+                update.moved,
+                target
+            );
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::NextVersion) = args.command {
+        let [ref repo] = repos[..] else {
+            anyhow::bail!("next-version works on a single repository");
+        };
+        let branch = config.branch.as_deref().unwrap_or("HEAD");
+        let next = repo.next_version(branch)?;
+        let current = next.current.as_deref().unwrap_or("no version tag");
+        if next.commit_count == 0 {
+            anyhow::bail!("No commits since {}; nothing to release", current);
+        }
+        if let Some(OutputFormat::Json) = config.format {
+            println!("{}", serde_json::to_string_pretty(&next)?);
+        } else {
+            eprintln!(
+                "{} -> {} ({} bump: {} commits, {} breaking)",
+                current, next.next, next.bump, next.commit_count, next.breaking_count
+            );
+            println!("{}", next.next);
+        }
+        return Ok(());
+    }
 
     // Resolve date range
-    let (since, until, used_default) = args.resolve_date_range();
+    let (range, used_default) = args.resolve_date_range(&config, chrono::Utc::now())?;
 
     if used_default {
         eprintln!("No date range specified, defaulting to last 1 day.");
     }
 
-    // Collect git data
-    let data = repo.collect_summary(
-        &args.branch,
-        since.as_deref(),
-        until.as_deref(),
-    )?;
+    let format = config.format.clone().unwrap_or(OutputFormat::Pretty);
+    let options = SummaryOptions {
+        branch: config.branch.clone().unwrap_or_else(|| "HEAD".to_string()),
+        from: args.from.clone(),
+        since_last_tag: args.since_last_tag,
+        since: range.git_since(),
+        until: range.git_until(),
+        date_label: Some(range.label),
+        next_version: args.next_version && matches!(format, OutputFormat::Json),
+        ..base_options(&args, &config)
+    };
+
+    // Collect git data, one thread per repository
+    let summaries = std::thread::scope(|scope| {
+        let handles: Vec<_> = repos
+            .iter()
+            .zip(&repo_paths)
+            .map(|(repo, path)| {
+                let options = &options;
+                scope.spawn(move || {
+                    repo.collect_summary(options)
+                        .with_context(|| format!("Failed to summarize repository {}", path))
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("summary thread panicked"))
+            .collect::<Result<Vec<_>>>()
+    })?;
+    let data = GitSummaryData::combine(summaries);
 
     if data.commits.is_empty() {
         eprintln!("No commits found for the specified date range.");
         return Ok(());
     }
 
-    // Get LLM summary (only if --llm flag is passed)
-    let summary = if args.llm {
-        let summarizer = Summarizer::new()?;
-        summarizer.summarize(&data).await?
+    let verbose = config.verbose.unwrap_or(false);
+    let quiet = config.quiet.unwrap_or(false);
+
+    // The pretty report streams the LLM summary into place as it is written
+    if config.llm.enabled.unwrap_or(false) && matches!(format, OutputFormat::Pretty) {
+        let summarizer = summarizer(&config)?;
+        let mut stdout = std::io::stdout();
+        print!("{}", PrettyFormatter.header(&data, quiet));
+        stdout.flush()?;
+        summarizer
+            .summarize_streaming(&data, &mut |text| {
+                print!("{}", text);
+                let _ = stdout.flush();
+            })
+            .await?;
+        println!("{}", PrettyFormatter.footer(&data, verbose, quiet));
+        return Ok(());
+    }
+
+    // Get LLM summary (only if --llm is passed or enabled in the config)
+    let summary = if config.llm.enabled.unwrap_or(false) {
+        summarizer(&config)?.summarize(&data).await?
     } else {
         "(LLM summary skipped - use --llm to enable)".to_string()
     };
 
     // Format output
-    let formatter: Box<dyn Formatter> = match args.format {
+    let formatter: Box<dyn Formatter> = match format {
         OutputFormat::Pretty => Box::new(PrettyFormatter),
         OutputFormat::Markdown => Box::new(MarkdownFormatter),
         OutputFormat::Json => Box::new(JsonFormatter),
     };
 
-    let output = formatter.format(&data, &summary, args.verbose, args.quiet);
+    let output = formatter.format(&data, &summary, verbose, quiet);
     println!("{}", output);
 
     Ok(())
 }
+
+/// Options shared by every report: identities, areas and exclusions
+fn base_options(args: &Args, config: &Config) -> SummaryOptions {
+    SummaryOptions {
+        authors: args.author.clone(),
+        mailmap: args.mailmap.clone(),
+        co_author_credit: match args.co_author_credit {
+            CoAuthorCredit::Full => git::CoAuthorCredit::Full,
+            CoAuthorCredit::Split => git::CoAuthorCredit::Split,
+        },
+        area_grouping: match config.group_by.clone().unwrap_or(GroupBy::Directory) {
+            GroupBy::Directory => git::AreaGrouping::Directory,
+            GroupBy::Package => git::AreaGrouping::Package,
+        },
+        area_depth: config.area_depth.unwrap_or(1),
+        area_rules: config.areas.clone().unwrap_or_default(),
+        group_by_owner: config.by_owner.unwrap_or(false),
+        excludes: config.exclude.clone().unwrap_or_default(),
+        includes: config.include.clone().unwrap_or_default(),
+        default_excludes: !args.no_default_excludes,
+        ..Default::default()
+    }
+}
+
+fn summarizer(config: &Config) -> Result<Summarizer> {
+    let settings = llm::ProviderSettings {
+        model: config.llm.model.clone(),
+        max_output_tokens: config
+            .llm
+            .max_output_tokens
+            .unwrap_or(llm::DEFAULT_MAX_OUTPUT_TOKENS),
+        base_url: config.llm.api_base_url.clone(),
+        timeout: match config.llm.timeout.unwrap_or(llm::DEFAULT_TIMEOUT_SECS) {
+            0 => None,
+            secs => Some(std::time::Duration::from_secs(secs)),
+        },
+        proxy: config.llm.proxy.clone(),
+        max_retries: config.llm.max_retries.unwrap_or(llm::DEFAULT_MAX_RETRIES),
+    };
+    if settings.max_output_tokens == 0 {
+        anyhow::bail!("--max-output-tokens must be at least 1");
+    }
+    let provider: Box<dyn llm::LlmProvider> =
+        match config.llm.provider.clone().unwrap_or(Provider::Anthropic) {
+            Provider::Anthropic => Box::new(llm::AnthropicProvider::from_env(&settings)?),
+            Provider::OpenAi => Box::new(llm::OpenAiProvider::from_env(&settings)?),
+            Provider::Ollama => Box::new(llm::OllamaProvider::from_env(&settings)?),
+        };
+    Ok(Summarizer::new(
+        provider,
+        config.prompt.style.unwrap_or_default(),
+        config.prompt.instructions.clone(),
+    ))
+}
+
+/// The settings given as flags, as the topmost configuration layer
+fn cli_config(args: &Args) -> Config {
+    Config {
+        range: args.range.clone(),
+        tz: args.tz.clone(),
+        format: args.format.clone(),
+        verbose: args.verbose.then_some(true),
+        quiet: args.quiet.then_some(true),
+        branch: args.branch.clone().or_else(|| args.to.clone()),
+        group_by: args.group_by.clone(),
+        area_depth: args.area_depth,
+        by_owner: args.by_owner.then_some(true),
+        exclude: Some(args.exclude.clone()).filter(|e| !e.is_empty()),
+        include: Some(args.include.clone()).filter(|i| !i.is_empty()),
+        llm: LlmConfig {
+            enabled: args.llm.then_some(true),
+            provider: args.provider.clone(),
+            model: args.model.clone(),
+            max_output_tokens: args.max_output_tokens,
+            api_base_url: args.api_base_url.clone(),
+            timeout: args.timeout,
+            proxy: args.proxy.clone(),
+            max_retries: args.max_retries,
+        },
+        ..Default::default()
+    }
+}
+
+fn open_backend(backend: &Backend, path: &str) -> Result<Box<dyn GitBackend>> {
+    Ok(match backend {
+        Backend::Git => Box::new(SubprocessBackend::new(path)),
+        #[cfg(feature = "native")]
+        Backend::Native => Box::new(git::NativeBackend::open(path)?),
+        #[cfg(not(feature = "native"))]
+        Backend::Native => {
+            anyhow::bail!("The native backend requires building with `--features native`")
+        }
+    })
+}
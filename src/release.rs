@@ -0,0 +1,284 @@
+use crate::git::{Commit, GitSummaryData, RevRange};
+
+/// Section of the release notes a commit is listed under, in display order
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Category {
+    Breaking,
+    Features,
+    Fixes,
+    Performance,
+    Documentation,
+    Other,
+}
+
+impl Category {
+    pub fn title(&self) -> &'static str {
+        match self {
+            Category::Breaking => "Breaking Changes",
+            Category::Features => "Features",
+            Category::Fixes => "Fixes",
+            Category::Performance => "Performance",
+            Category::Documentation => "Documentation",
+            Category::Other => "Other Changes",
+        }
+    }
+}
+
+/// One commit as it appears in the release notes
+#[derive(Debug)]
+pub struct Entry<'a> {
+    pub commit: &'a Commit,
+    pub scope: Option<String>,
+    /// Subject without its `type(scope):` prefix, capitalized
+    pub description: String,
+}
+
+pub struct ReleaseNotes<'a> {
+    /// Tag the notes are for, or `None` for unreleased changes
+    pub version: Option<String>,
+    pub data: &'a GitSummaryData,
+    /// Browser URL of the repository, for commit and compare links
+    pub web_url: Option<String>,
+    /// Non-empty categories with their commits, newest first
+    pub sections: Vec<(Category, Vec<Entry<'a>>)>,
+}
+
+impl<'a> ReleaseNotes<'a> {
+    /// Categorize the commits of `data`, leaving out merges
+    pub fn new(data: &'a GitSummaryData, version: Option<String>, web_url: Option<String>) -> Self {
+        let mut sections: Vec<(Category, Vec<Entry>)> = Vec::new();
+        for commit in data.commits.iter().filter(|c| c.parents.len() <= 1) {
+            let (category, scope, description) = categorize(commit);
+            let entry = Entry {
+                commit,
+                scope,
+                description,
+            };
+            match sections.iter_mut().find(|(c, _)| *c == category) {
+                Some((_, entries)) => entries.push(entry),
+                None => sections.push((category, vec![entry])),
+            }
+        }
+        sections.sort_by_key(|(category, _)| *category);
+
+        Self {
+            version,
+            data,
+            web_url,
+            sections,
+        }
+    }
+
+    /// Scope, description and commit link of one entry
+    pub fn entry_markdown(&self, entry: &Entry) -> String {
+        let mut text = String::new();
+        if let Some(ref scope) = entry.scope {
+            text.push_str(&format!("**{}:** ", scope));
+        }
+        text.push_str(&entry.description);
+        match self.web_url {
+            Some(ref url) => text.push_str(&format!(
+                " ([{}]({}/commit/{}))",
+                entry.commit.short_hash, url, entry.commit.hash
+            )),
+            None => text.push_str(&format!(" ({})", entry.commit.short_hash)),
+        }
+        text
+    }
+
+    /// Markdown for a GitHub release body, with `prose` from the LLM on top
+    pub fn to_markdown(&self, prose: Option<&str>) -> String {
+        let mut output = String::new();
+
+        if let Some(prose) = prose {
+            output.push_str(prose.trim());
+            output.push_str("\n\n");
+        }
+
+        output.push_str("## What's Changed\n\n");
+        for (category, entries) in &self.sections {
+            output.push_str(&format!("### {}\n\n", category.title()));
+            for entry in entries {
+                output.push_str(&format!("- {}", self.entry_markdown(entry)));
+                let mut people = vec![entry.commit.author.name.as_str()];
+                people.extend(entry.commit.co_authors.iter().map(|p| p.name.as_str()));
+                output.push_str(&format!(" by {}\n", people.join(", ")));
+                if let Some(ref note) = entry.commit.conventional.breaking_change {
+                    output.push_str(&format!("  {}\n", note));
+                }
+            }
+            output.push('\n');
+        }
+
+        if !self.data.author_stats.is_empty() {
+            output.push_str("## Contributors\n\n");
+            output.push_str("Thanks to everyone who contributed to this release:\n\n");
+            for author in &self.data.author_stats {
+                output.push_str(&format!(
+                    "- {} ({} commit{})\n",
+                    author.name,
+                    author.commit_count,
+                    if author.commit_count == 1 { "" } else { "s" }
+                ));
+            }
+            output.push('\n');
+        }
+
+        let range = RevRange::parse(&self.data.branch);
+        if let (Some(url), Some(from)) = (&self.web_url, &range.from) {
+            output.push_str(&format!(
+                "**Full Changelog**: {}/compare/{}...{}\n",
+                url, from, range.to
+            ));
+        }
+
+        output.trim_end().to_string()
+    }
+}
+
+/// Category, scope and description of a commit. The Conventional Commits
+/// type is used when present, otherwise the subject's first word.
+fn categorize(commit: &Commit) -> (Category, Option<String>, String) {
+    let conventional = &commit.conventional;
+    let description = capitalize(&conventional.description);
+    if conventional.breaking {
+        return (Category::Breaking, conventional.scope.clone(), description);
+    }
+
+    if let Some(ref kind) = conventional.kind {
+        let category = match kind.as_str() {
+            "feat" | "feature" => Category::Features,
+            "fix" | "bugfix" => Category::Fixes,
+            "perf" => Category::Performance,
+            "docs" | "doc" => Category::Documentation,
+            _ => Category::Other,
+        };
+        return (category, conventional.scope.clone(), description);
+    }
+
+    let first_word = description
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let category = match first_word.as_str() {
+        "add" | "adds" | "added" | "implement" | "implements" | "implemented" | "introduce"
+        | "introduces" | "introduced" | "support" | "supports" | "allow" | "allows" | "enable"
+        | "enables" => Category::Features,
+        "fix" | "fixes" | "fixed" | "resolve" | "resolves" | "resolved" | "correct"
+        | "corrects" | "corrected" => Category::Fixes,
+        "optimize" | "optimizes" | "optimized" | "optimise" | "optimises" | "optimised"
+        | "speed" | "speeds" => Category::Performance,
+        "document" | "documents" | "documented" | "docs" => Category::Documentation,
+        _ => Category::Other,
+    };
+    (category, None, description)
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::fake::{commit, FakeBackend};
+    use crate::git::{GitRepo, SummaryOptions};
+
+    fn data() -> GitSummaryData {
+        let mut merge = commit("m000000aa", "Merge branch 'topic'");
+        merge.parents = vec!["f000000aa".to_string(), "d000000aa".to_string()];
+        let commits = vec![
+            merge,
+            commit("f000000aa", "fix(cli): handle empty input").by("Bob", "bob@example.com"),
+            commit("d000000aa", "docs: describe flags"),
+            commit("p000000aa", "perf: cache lookups"),
+            commit("o000000aa", "Bump dependencies"),
+            commit(
+                "b000000aa",
+                "feat(api)!: drop v1 endpoints\n\nBREAKING CHANGE: clients must use /v2",
+            ),
+            commit(
+                "a000000aa",
+                "feat: add export\n\nCo-authored-by: Carol <carol@example.com>",
+            ),
+            commit("s000000aa", "Add search"),
+            commit("r000000aa", "chore: release 1.0.0"),
+        ];
+        GitRepo::new(Box::new(
+            FakeBackend::new(commits).tag("v1.0.0", "r000000aa"),
+        ))
+        .collect_summary(&SummaryOptions {
+            branch: "v1.0.0..HEAD".to_string(),
+            ..Default::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn commits_are_grouped_in_category_order() {
+        let data = data();
+        let notes = ReleaseNotes::new(&data, Some("v1.1.0".to_string()), None);
+
+        let sections: Vec<(Category, Vec<&str>)> = notes
+            .sections
+            .iter()
+            .map(|(category, entries)| {
+                let hashes = entries
+                    .iter()
+                    .map(|e| e.commit.short_hash.as_str())
+                    .collect();
+                (*category, hashes)
+            })
+            .collect();
+        assert_eq!(
+            sections,
+            [
+                (Category::Breaking, vec!["b000000"]),
+                (Category::Features, vec!["a000000", "s000000"]),
+                (Category::Fixes, vec!["f000000"]),
+                (Category::Performance, vec!["p000000"]),
+                (Category::Documentation, vec!["d000000"]),
+                (Category::Other, vec!["o000000"]),
+            ]
+        );
+
+        let breaking = &notes.sections[0].1[0];
+        assert_eq!(breaking.scope.as_deref(), Some("api"));
+        assert_eq!(breaking.description, "Drop v1 endpoints");
+    }
+
+    #[test]
+    fn markdown_links_commits_and_credits_authors() {
+        let data = data();
+        let url = "https://github.com/acme/app";
+        let notes = ReleaseNotes::new(&data, None, Some(url.to_string()));
+        let markdown = notes.to_markdown(Some("A smaller, faster release.\n"));
+
+        assert!(markdown.starts_with("A smaller, faster release.\n\n## What's Changed\n\n"));
+        assert!(markdown.contains(
+            "### Breaking Changes\n\n\
+             - **api:** Drop v1 endpoints \
+             ([b000000](https://github.com/acme/app/commit/b000000aa)) by Alice\n  \
+             clients must use /v2\n"
+        ));
+        assert!(markdown.contains(
+            "- Add export ([a000000](https://github.com/acme/app/commit/a000000aa)) \
+             by Alice, Carol\n"
+        ));
+        assert!(markdown.contains("- **cli:** Handle empty input ([f000000]("));
+        assert!(!markdown.contains("Merge branch"));
+        assert!(markdown.contains("- Alice (7 commits)\n- Bob (1 commit)\n- Carol (1 commit)\n"));
+        assert!(markdown
+            .ends_with("**Full Changelog**: https://github.com/acme/app/compare/v1.0.0...main"));
+
+        let plain = ReleaseNotes::new(&data, None, None).to_markdown(None);
+        assert!(plain.starts_with("## What's Changed"));
+        assert!(plain.contains("- Bump dependencies (o000000) by Alice\n"));
+        assert!(!plain.contains("Full Changelog"));
+    }
+}
@@ -1,118 +1,296 @@
-use anyhow::{Context, Result};
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
 
-use crate::git::GitSummaryData;
+use crate::git::{Commit, GitSummaryData};
+use crate::llm::{self, LlmProvider};
+use crate::release::ReleaseNotes;
 
-/// Maximum input tokens for Claude API (leaving room for output tokens)
-const MAX_INPUT_TOKENS: usize = 195_000;
-
-/// Approximate characters per token (conservative estimate)
-const CHARS_PER_TOKEN: f64 = 3.5;
-
-#[derive(Debug, Serialize)]
-struct AnthropicRequest {
-    model: String,
-    max_tokens: u32,
-    messages: Vec<Message>,
+/// How the summary is written
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptStyle {
+    /// One bullet per theme
+    #[default]
+    Bullets,
+    /// A handful of one-line bullets, for standups
+    Brief,
+    /// Short paragraphs, for weekly updates and release announcements
+    Prose,
 }
 
-#[derive(Debug, Serialize)]
-struct Message {
-    role: String,
-    content: String,
-}
+/// Fewest tokens left for commits or summaries in each request of a
+/// summary in parts, below which the parts would be too small to be useful
+const MIN_PART_TOKENS: usize = 1024;
 
-#[derive(Debug, Deserialize)]
-struct AnthropicResponse {
-    content: Vec<ContentBlock>,
+/// Commits summarized on their own when a range is too large for one prompt
+struct Part {
+    /// Author dates (YYYY-MM-DD) of the first and last commit
+    from: String,
+    to: String,
+    commit_count: usize,
+    summary: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct ContentBlock {
-    text: String,
+impl Part {
+    fn period(&self) -> String {
+        if self.from == self.to {
+            self.from.clone()
+        } else {
+            format!("{} to {}", self.from, self.to)
+        }
+    }
 }
 
 pub struct Summarizer {
-    api_key: String,
-    model: String,
+    provider: Box<dyn LlmProvider>,
+    style: PromptStyle,
+    /// Extra instructions appended to the built-in ones
+    instructions: Option<String>,
 }
 
 impl Summarizer {
-    pub fn new() -> Result<Self> {
-        let api_key =
-            std::env::var("ANTHROPIC_API_KEY").context("ANTHROPIC_API_KEY not set")?;
+    pub fn new(
+        provider: Box<dyn LlmProvider>,
+        style: PromptStyle,
+        instructions: Option<String>,
+    ) -> Self {
+        Self {
+            provider,
+            style,
+            instructions,
+        }
+    }
+
+    pub async fn summarize(&self, data: &GitSummaryData) -> Result<String> {
+        self.summarize_with(data, None).await
+    }
 
-        Ok(Self {
-            api_key,
-            model: "claude-sonnet-4-20250514".to_string(),
-        })
+    /// Like `summarize`, also passing the summary to `on_text` piece by piece
+    /// as the model writes it
+    pub async fn summarize_streaming(
+        &self,
+        data: &GitSummaryData,
+        on_text: &mut (dyn FnMut(&str) + Send),
+    ) -> Result<String> {
+        self.summarize_with(data, Some(on_text)).await
     }
 
-    /// Estimate the number of tokens in a string
-    fn estimate_tokens(text: &str) -> usize {
-        (text.len() as f64 / CHARS_PER_TOKEN).ceil() as usize
+    /// Summarize `data` in one request, or, when the commits don't fit the
+    /// model's context window, summarize consecutive periods separately and
+    /// then the summaries of those periods. Only the final summary streams.
+    async fn summarize_with(
+        &self,
+        data: &GitSummaryData,
+        on_text: Option<&mut (dyn FnMut(&str) + Send)>,
+    ) -> Result<String> {
+        let prompt = self.build_prompt(data, None);
+        let estimated_tokens = llm::estimate_tokens(&prompt);
+        let max_input_tokens = self.provider.max_input_tokens();
+        if estimated_tokens <= max_input_tokens {
+            return self.complete(prompt, on_text).await;
+        }
+
+        let mut parts = self.summarize_periods(data).await?;
+        loop {
+            let prompt = self.build_prompt(data, Some(&parts));
+            if parts.len() == 1 || llm::estimate_tokens(&prompt) <= max_input_tokens {
+                eprintln!("Writing the final summary...");
+                return self.complete(prompt, on_text).await;
+            }
+            parts = self.merge_parts(data, parts).await?;
+        }
     }
 
-    pub async fn summarize(&self, data: &GitSummaryData) -> Result<String> {
-        let prompt = self.build_prompt(data);
+    /// User-facing introduction for release notes
+    pub async fn summarize_release(&self, notes: &ReleaseNotes<'_>) -> Result<String> {
+        self.complete(self.build_release_prompt(notes), None).await
+    }
 
-        let estimated_tokens = Self::estimate_tokens(&prompt);
-        if estimated_tokens > MAX_INPUT_TOKENS {
+    /// Send `prompt` to the provider and return the text of the reply,
+    /// streaming it to `on_text` if given
+    async fn complete(
+        &self,
+        prompt: String,
+        on_text: Option<&mut (dyn FnMut(&str) + Send)>,
+    ) -> Result<String> {
+        let estimated_tokens = llm::estimate_tokens(&prompt);
+        let max_input_tokens = self.provider.max_input_tokens();
+        if estimated_tokens > max_input_tokens {
             anyhow::bail!(
-                "Input too large for Claude API: ~{} tokens (limit: {} tokens). \
+                "Input too large for {}: ~{} tokens (limit: {} tokens). \
                 Try using a shorter date range with --since or --until, \
                 or reduce the number of commits.",
+                self.provider.model(),
                 estimated_tokens,
-                MAX_INPUT_TOKENS
+                max_input_tokens
             );
         }
 
-        let request = AnthropicRequest {
-            model: self.model.clone(),
-            max_tokens: 1024,
-            messages: vec![Message {
-                role: "user".to_string(),
-                content: prompt,
-            }],
-        };
+        match on_text {
+            Some(on_text) => self.provider.complete_stream(&prompt, on_text).await,
+            None => self.provider.complete(&prompt).await,
+        }
+    }
 
-        let client = reqwest::Client::new();
-        let response = client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("content-type", "application/json")
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send request to Anthropic API")?;
+    /// Split the commits, oldest first, into runs that fit one prompt each
+    /// and summarize every run
+    async fn summarize_periods(&self, data: &GitSummaryData) -> Result<Vec<Part>> {
+        let mut commits: Vec<&Commit> = data.commits.iter().collect();
+        // ISO 8601 timestamps in mixed offsets don't sort as strings
+        commits.sort_by_key(|c| {
+            chrono::DateTime::parse_from_rfc3339(&c.timestamp)
+                .map(|t| t.timestamp())
+                .unwrap_or_default()
+        });
+
+        // The prompt without its commit messages, with a part line as long
+        // as any will be
+        let sample = &commits[..commits.len().min(1)];
+        let mut messages = String::new();
+        push_commit_messages(&mut messages, sample.iter().copied());
+        let header = self.build_period_prompt(data, sample, commits.len(), commits.len());
+        let budget = self.part_budget(&header[..header.len() - messages.len()])?;
+        let mut chunks: Vec<Vec<&Commit>> = Vec::new();
+        let mut chunk = Vec::new();
+        let mut chunk_tokens = 0;
+        for commit in commits {
+            let mut text = String::new();
+            push_commit_messages(&mut text, [commit]);
+            let tokens = llm::estimate_tokens(&text);
+            if !chunk.is_empty() && chunk_tokens + tokens > budget {
+                chunks.push(std::mem::take(&mut chunk));
+                chunk_tokens = 0;
+            }
+            chunk.push(commit);
+            chunk_tokens += tokens;
+        }
+        chunks.push(chunk);
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            anyhow::bail!("Anthropic API error ({}): {}", status, body);
+        eprintln!(
+            "{} commits are too many for one request to {}; summarizing them in {} parts.",
+            data.commits.len(),
+            self.provider.model(),
+            chunks.len()
+        );
+        let mut parts = Vec::with_capacity(chunks.len());
+        for (index, chunk) in chunks.iter().enumerate() {
+            let part = Part {
+                from: date(chunk[0]).to_string(),
+                to: date(chunk[chunk.len() - 1]).to_string(),
+                commit_count: chunk.len(),
+                summary: String::new(),
+            };
+            eprintln!(
+                "Summarizing part {}/{} ({}, {} commits)...",
+                index + 1,
+                chunks.len(),
+                part.period(),
+                part.commit_count
+            );
+            let prompt = self.build_period_prompt(data, chunk, index + 1, chunks.len());
+            parts.push(Part {
+                summary: self.complete(prompt, None).await?,
+                ..part
+            });
         }
+        Ok(parts)
+    }
 
-        let result: AnthropicResponse = response
-            .json()
-            .await
-            .context("Failed to parse Anthropic response")?;
+    /// Tokens left for the commits or summaries of one part after `header`,
+    /// the rest of its prompt
+    fn part_budget(&self, header: &str) -> Result<usize> {
+        let max_input_tokens = self.provider.max_input_tokens();
+        let budget = max_input_tokens.saturating_sub(llm::estimate_tokens(header));
+        if budget < MIN_PART_TOKENS {
+            anyhow::bail!(
+                "Input too large for {}, and its limit of {} input tokens leaves too little \
+                room to summarize it in parts. Try a lower --max-output-tokens, a model with \
+                a larger context window, or a shorter date range with --since or --until.",
+                self.provider.model(),
+                max_input_tokens
+            );
+        }
+        Ok(budget)
+    }
+
+    /// Combine runs of consecutive parts whose summaries together fit one
+    /// prompt, for when even the summaries are too long for the final request
+    async fn merge_parts(&self, data: &GitSummaryData, parts: Vec<Part>) -> Result<Vec<Part>> {
+        let budget = self.part_budget(&self.build_merge_prompt(data, &[]))?;
+        let mut groups: Vec<Vec<Part>> = Vec::new();
+        let mut group = Vec::new();
+        let mut group_tokens = 0;
+        for part in parts {
+            let mut text = String::new();
+            push_parts(&mut text, std::slice::from_ref(&part));
+            let tokens = llm::estimate_tokens(&text);
+            if !group.is_empty() && group_tokens + tokens > budget {
+                groups.push(std::mem::take(&mut group));
+                group_tokens = 0;
+            }
+            group_tokens += tokens;
+            group.push(part);
+        }
+        groups.push(group);
+
+        if groups.iter().all(|group| group.len() == 1) {
+            anyhow::bail!(
+                "Input too large for {} even after summarizing it in parts. \
+                Try using a shorter date range with --since or --until.",
+                self.provider.model()
+            );
+        }
 
-        result
-            .content
-            .first()
-            .map(|c| c.text.clone())
-            .context("No content in response")
+        eprintln!("Combining the summaries into {} parts...", groups.len());
+        let mut merged = Vec::with_capacity(groups.len());
+        for group in groups {
+            if group.len() == 1 {
+                merged.extend(group);
+                continue;
+            }
+            let prompt = self.build_merge_prompt(data, &group);
+            merged.push(Part {
+                from: group[0].from.clone(),
+                to: group[group.len() - 1].to.clone(),
+                commit_count: group.iter().map(|p| p.commit_count).sum(),
+                summary: self.complete(prompt, None).await?,
+            });
+        }
+        Ok(merged)
     }
 
-    fn build_prompt(&self, data: &GitSummaryData) -> String {
+    /// `parts` replaces the commit messages once the range has been
+    /// summarized in parts
+    fn build_prompt(&self, data: &GitSummaryData, parts: Option<&[Part]>) -> String {
         let mut prompt = String::new();
 
-        prompt.push_str("Summarize the following git commits as a bulleted list. ");
-        prompt.push_str("Each bullet should describe a theme or area of work. ");
-        prompt.push_str("Group related changes together conceptually. ");
-        prompt.push_str("Use simple '- ' for bullets. Keep each bullet to 1-2 sentences. ");
-        prompt.push_str("Do not list individual commits or mention specific authors.\n\n");
+        match self.style {
+            PromptStyle::Bullets => {
+                prompt.push_str("Summarize the following git commits as a bulleted list. ");
+                prompt.push_str("Each bullet should describe a theme or area of work. ");
+                prompt.push_str("Group related changes together conceptually. ");
+                prompt.push_str("Use simple '- ' for bullets. Keep each bullet to 1-2 sentences. ");
+            }
+            PromptStyle::Brief => {
+                prompt.push_str("Summarize the following git commits as a standup update ");
+                prompt.push_str("of at most five bullets. ");
+                prompt.push_str(
+                    "Use simple '- ' for bullets. Keep each bullet to one short sentence. ",
+                );
+            }
+            PromptStyle::Prose => {
+                prompt.push_str("Summarize the following git commits in two or three short ");
+                prompt.push_str("paragraphs of plain prose, without bullets or headings. ");
+                prompt.push_str("Group related changes together conceptually. ");
+            }
+        }
+        prompt.push_str("Do not list individual commits. ");
+        prompt.push_str("Where one person clearly drove a theme, you may credit them by name.\n");
+        if let Some(ref instructions) = self.instructions {
+            prompt.push_str(instructions.trim());
+            prompt.push('\n');
+        }
+        prompt.push('\n');
 
         prompt.push_str(&format!(
             "Branch: {}\nDate range: {}\nTotal commits: {}\n\n",
@@ -121,6 +299,21 @@ impl Summarizer {
             data.commits.len()
         ));
 
+        if !data.repos.is_empty() {
+            prompt.push_str("Repositories:\n");
+            for repo in &data.repos {
+                prompt.push_str(&format!(
+                    "  {} ({}) - {} commits, +{}/-{} lines\n",
+                    repo.repo,
+                    repo.branch,
+                    repo.commits.len(),
+                    repo.total_additions,
+                    repo.total_deletions
+                ));
+            }
+            prompt.push('\n');
+        }
+
         prompt.push_str("Areas changed:\n");
         for area in &data.area_stats {
             prompt.push_str(&format!(
@@ -129,11 +322,286 @@ impl Summarizer {
             ));
         }
 
+        // Conventional Commits types tell features from fixes and chores
+        if data.commits.iter().any(|c| c.conventional.kind.is_some()) {
+            prompt.push_str("\nCommit types (Conventional Commits; group features, fixes and ");
+            prompt.push_str("maintenance separately and call out breaking changes):\n");
+            for kind in &data.type_stats {
+                prompt.push_str(&format!(
+                    "  {} - {} commits ({} breaking), +{}/-{} lines\n",
+                    kind.kind,
+                    kind.commit_count,
+                    kind.breaking_count,
+                    kind.additions,
+                    kind.deletions
+                ));
+            }
+        }
+
+        if !data.owner_stats.is_empty() {
+            prompt.push_str("\nOwning teams (from CODEOWNERS):\n");
+            for owner in &data.owner_stats {
+                prompt.push_str(&format!(
+                    "  {} - {} commits, +{}/-{} lines\n",
+                    owner.owner, owner.commit_count, owner.additions, owner.deletions
+                ));
+            }
+        }
+
+        if !data.excluded_stats.is_empty() {
+            prompt.push_str("\nExcluded from the line counts above (not meaningful work):\n");
+            for excluded in &data.excluded_stats {
+                prompt.push_str(&format!(
+                    "  {} - {} files, +{}/-{} lines\n",
+                    excluded.reason, excluded.file_count, excluded.additions, excluded.deletions
+                ));
+            }
+        }
+
+        prompt.push_str("\nContributors:\n");
+        for author in &data.author_stats {
+            prompt.push_str(&format!(
+                "  {} - {} commits ({} co-authored), +{}/-{} lines\n",
+                author.name,
+                author.commit_count,
+                author.co_authored_count,
+                author.additions,
+                author.deletions
+            ));
+        }
+
+        if let Some(parts) = parts {
+            prompt.push_str("\nSummaries of the commits, one per period, oldest first ");
+            prompt.push_str("(the range was too large to show every commit):\n");
+            push_parts(&mut prompt, parts);
+            return prompt;
+        }
+
         prompt.push_str("\nCommit messages:\n");
-        for commit in &data.commits {
-            prompt.push_str(&format!("  - {}\n", commit.message));
+        if data.repos.is_empty() {
+            push_commit_messages(&mut prompt, &data.commits);
+        } else {
+            for repo in &data.repos {
+                prompt.push_str(&format!("  [{}]\n", repo.repo));
+                push_commit_messages(&mut prompt, &repo.commits);
+            }
         }
 
         prompt
     }
+
+    /// Notes on one run of commits, to be combined with the others later
+    fn build_period_prompt(
+        &self,
+        data: &GitSummaryData,
+        commits: &[&Commit],
+        index: usize,
+        count: usize,
+    ) -> String {
+        let mut prompt = String::new();
+
+        prompt.push_str("The following git commits are one part of a range too large to ");
+        prompt.push_str("summarize at once; your notes will be combined with those for the ");
+        prompt.push_str("other parts. Write concise notes as '- ' bullets, one per theme or ");
+        prompt.push_str("area of work: what changed, why, and who drove it. Call out breaking ");
+        prompt.push_str("changes. Do not list individual commits and do not add a heading.\n\n");
+
+        prompt.push_str(&format!("Branch: {}\n", data.branch));
+        if let (Some(first), Some(last)) = (commits.first(), commits.last()) {
+            prompt.push_str(&format!(
+                "Part {} of {}: {} to {}, {} commits\n",
+                index,
+                count,
+                date(first),
+                date(last),
+                commits.len()
+            ));
+        }
+
+        prompt.push_str("\nCommit messages:\n");
+        push_commit_messages(&mut prompt, commits.iter().copied());
+
+        prompt
+    }
+
+    /// Notes combining the summaries of consecutive periods
+    fn build_merge_prompt(&self, data: &GitSummaryData, parts: &[Part]) -> String {
+        let mut prompt = String::new();
+
+        prompt.push_str("The following notes summarize consecutive periods of git history. ");
+        prompt.push_str("Combine them into one set of concise notes as '- ' bullets, one per ");
+        prompt.push_str("theme or area of work, keeping who drove each theme and any breaking ");
+        prompt.push_str("changes. Do not add a heading.\n\n");
+
+        prompt.push_str(&format!("Branch: {}\n\nSummaries:\n", data.branch));
+        push_parts(&mut prompt, parts);
+
+        prompt
+    }
+
+    fn build_release_prompt(&self, notes: &ReleaseNotes<'_>) -> String {
+        let mut prompt = String::new();
+
+        let version = notes.version.as_deref().unwrap_or("the upcoming release");
+        prompt.push_str(&format!(
+            "Write the introduction to the release notes for {} of {}. ",
+            version, notes.data.repo
+        ));
+        prompt.push_str("The readers are users of the software, not its developers: ");
+        prompt
+            .push_str("describe what they can now do, which problems are fixed, what got faster ");
+        prompt.push_str("and what they must change when upgrading because of breaking changes. ");
+        prompt.push_str("Leave out refactoring, tests, CI and other internal work. ");
+        prompt.push_str("Write one or two short paragraphs, optionally followed by a few '- ' ");
+        prompt.push_str("bullets for highlights. Do not use headings and do not mention commit ");
+        prompt.push_str("hashes; a categorized list of every change follows your text.\n");
+        if let Some(ref instructions) = self.instructions {
+            prompt.push_str(instructions.trim());
+            prompt.push('\n');
+        }
+        prompt.push('\n');
+
+        prompt.push_str(&format!("Range: {}\n", notes.data.branch));
+        for (category, entries) in &notes.sections {
+            prompt.push_str(&format!("\n{}:\n", category.title()));
+            push_commit_messages(&mut prompt, entries.iter().map(|e| e.commit));
+        }
+
+        prompt
+    }
+}
+
+/// Author date of `commit` as YYYY-MM-DD
+fn date(commit: &Commit) -> &str {
+    commit.timestamp.get(..10).unwrap_or(&commit.timestamp)
+}
+
+fn push_parts(prompt: &mut String, parts: &[Part]) {
+    for part in parts {
+        prompt.push_str(&format!(
+            "  [{}, {} commits]\n",
+            part.period(),
+            part.commit_count
+        ));
+        for line in part.summary.lines().filter(|l| !l.trim().is_empty()) {
+            prompt.push_str(&format!("    {}\n", line.trim_end()));
+        }
+    }
+}
+
+fn push_commit_messages<'a>(prompt: &mut String, commits: impl IntoIterator<Item = &'a Commit>) {
+    for commit in commits {
+        let mut people = vec![commit.author.name.as_str()];
+        people.extend(commit.co_authors.iter().map(|p| p.name.as_str()));
+        let breaking = if commit.conventional.breaking {
+            " [BREAKING]"
+        } else {
+            ""
+        };
+        prompt.push_str(&format!(
+            "  - {} ({}){}\n",
+            commit.message,
+            people.join(", "),
+            breaking
+        ));
+        // The body often carries the real explanation behind a change
+        for line in commit.body.lines().filter(|l| !l.trim().is_empty()) {
+            prompt.push_str(&format!("      {}\n", line.trim_end()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::fake::{commit, FakeBackend};
+    use crate::git::{GitRepo, SummaryOptions};
+    use crate::llm::BoxFuture;
+    use std::sync::{Arc, Mutex};
+
+    /// Replies "- notes" to every prompt and records them
+    struct Recorder {
+        max_input_tokens: usize,
+        prompts: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl LlmProvider for Recorder {
+        fn name(&self) -> &str {
+            "Test"
+        }
+
+        fn model(&self) -> &str {
+            "test-model"
+        }
+
+        fn max_output_tokens(&self) -> u32 {
+            256
+        }
+
+        fn max_input_tokens(&self) -> usize {
+            self.max_input_tokens
+        }
+
+        fn complete<'a>(&'a self, prompt: &'a str) -> BoxFuture<'a, Result<String>> {
+            self.prompts.lock().unwrap().push(prompt.to_string());
+            Box::pin(async { Ok("- notes".to_string()) })
+        }
+    }
+
+    fn summarizer(max_input_tokens: usize) -> (Summarizer, Arc<Mutex<Vec<String>>>) {
+        let prompts = Arc::new(Mutex::new(Vec::new()));
+        let provider = Recorder {
+            max_input_tokens,
+            prompts: prompts.clone(),
+        };
+        (
+            Summarizer::new(Box::new(provider), PromptStyle::default(), None),
+            prompts,
+        )
+    }
+
+    /// 200 commits with long bodies, newest first
+    fn data() -> GitSummaryData {
+        let body = "Details of the change. ".repeat(20);
+        let commits = (0..200)
+            .rev()
+            .map(|i| {
+                let day = format!("2024-{:02}-{:02}T10:00:00+00:00", i / 28 + 1, i % 28 + 1);
+                let message = format!("fix: change {}\n\n{}", i, body);
+                commit(&format!("{:040x}", i), &message).at(&day)
+            })
+            .collect();
+        GitRepo::new(Box::new(FakeBackend::new(commits)))
+            .collect_summary(&SummaryOptions {
+                branch: "HEAD".to_string(),
+                ..Default::default()
+            })
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn large_ranges_are_summarized_in_parts() {
+        let (summarizer, prompts) = summarizer(8_000);
+
+        assert_eq!(summarizer.summarize(&data()).await.unwrap(), "- notes");
+
+        let prompts = prompts.lock().unwrap();
+        assert!(prompts.len() > 2, "{} requests", prompts.len());
+        assert!(prompts
+            .iter()
+            .all(|prompt| llm::estimate_tokens(prompt) <= 8_000));
+        // Parts go oldest first, then the final summary of all of them
+        assert!(prompts[0].contains("- fix: change 0 (Alice)"));
+        assert!(prompts.last().unwrap().contains("2024-01-01 to"));
+    }
+
+    #[tokio::test]
+    async fn tiny_limits_fail_before_any_request() {
+        let (summarizer, prompts) = summarizer(500);
+
+        let error = summarizer.summarize(&data()).await.unwrap_err();
+
+        assert!(error.to_string().contains("too little room"), "{}", error);
+        assert!(prompts.lock().unwrap().is_empty());
+    }
 }